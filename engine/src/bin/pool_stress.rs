@@ -0,0 +1,159 @@
+//! Plays many concurrent wagers against one Redis pool and checks that every pool cycle
+//! had exactly one winner, that no contribution was lost or paid twice, and that per-site
+//! contributions add up to each cycle's totals.
+//!
+//! ```sh
+//! cargo run -p engine --bin pool_stress -- --redis-url redis://127.0.0.1:6379
+//! ```
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::ensure;
+use chrono::Utc;
+use clap::Parser;
+use engine::{
+    domain::models::{Pool, PoolConfig, PoolStatus},
+    pool_store::{PoolPlay, PoolStore, redis_store::RedisPoolStore},
+};
+use futures::{StreamExt, stream};
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+#[derive(Parser)]
+struct Args {
+    #[arg(long, default_value = "redis://127.0.0.1:6379")]
+    redis_url: String,
+    /// Wagers to play.
+    #[arg(long, default_value_t = 20_000)]
+    wagers: usize,
+    /// Wagers in flight at once.
+    #[arg(long, default_value_t = 256)]
+    concurrency: usize,
+    /// Separate Redis connections the wagers are spread over.
+    #[arg(long, default_value_t = 8)]
+    clients: usize,
+    #[arg(long, default_value_t = 0.01)]
+    hit_probability: f64,
+    #[arg(long, default_value_t = 7)]
+    contribution: u64,
+    /// Sites the wagers are spread over, as for a network pool.
+    #[arg(long, default_value_t = 3)]
+    sites: usize,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let pool = Pool {
+        id: format!("stress-{}", Uuid::new_v4()),
+        status: PoolStatus::Active,
+        version: 1,
+        config: PoolConfig {
+            site_ids: Vec::new(),
+            seed: 1_000,
+            contribution_rate: 0.0,
+            site_rates: BTreeMap::new(),
+            hit_probability: args.hit_probability,
+        },
+        updated_at: Utc::now(),
+    };
+
+    let mut stores: Vec<Arc<dyn PoolStore>> = Vec::new();
+    for _ in 0..args.clients.max(1) {
+        stores.push(Arc::new(RedisPoolStore::new(&args.redis_url).await?));
+    }
+
+    let plays: Vec<PoolPlay> = stream::iter(0..args.wagers)
+        .map(|i| {
+            let store = stores[i % stores.len()].clone();
+            let pool = &pool;
+            let site_id = (i % args.sites.max(1)) as i32 + 1;
+            async move {
+                store
+                    .contribute(
+                        pool,
+                        Uuid::new_v4(),
+                        site_id,
+                        args.contribution,
+                        rand::random::<f64>(),
+                    )
+                    .await
+            }
+        })
+        .buffer_unordered(args.concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<anyhow::Result<_>>()?;
+
+    let client = redis::Client::open(args.redis_url.as_str())?;
+    let mut redis = client.get_multiplexed_async_connection().await?;
+    let key = RedisPoolStore::key(&pool.id);
+    let (final_value, final_cycle): (u64, u64) = redis::cmd("HMGET")
+        .arg(&key)
+        .arg("value")
+        .arg("cycle")
+        .query_async(&mut redis)
+        .await?;
+    let _: () = redis.del(&key).await?;
+
+    let mut wins_by_cycle = BTreeMap::<u64, usize>::new();
+    for play in plays.iter().filter(|play| play.won) {
+        *wins_by_cycle.entry(play.cycle_id).or_default() += 1;
+    }
+    let wins = wins_by_cycle.values().sum::<usize>() as u64;
+    let paid_out: u64 = plays
+        .iter()
+        .filter(|play| play.won)
+        .map(|play| play.pool_value)
+        .sum();
+    let contributed = args.contribution * args.wagers as u64;
+
+    println!(
+        "wagers={} wins={} completed_cycles={} paid_out={} final_value={}",
+        args.wagers,
+        wins,
+        final_cycle - 1,
+        paid_out,
+        final_value
+    );
+
+    for (cycle, count) in &wins_by_cycle {
+        ensure!(*count == 1, "cycle {cycle} had {count} winners");
+    }
+    for (play, cycle) in plays
+        .iter()
+        .filter_map(|play| Some((play, play.completed_cycle.as_ref()?)))
+    {
+        let site_contributions: u64 = cycle
+            .site_contributions
+            .iter()
+            .map(|s| s.contributions)
+            .sum();
+        let site_wagers: u64 = cycle.site_contributions.iter().map(|s| s.wager_count).sum();
+        ensure!(
+            site_contributions == cycle.total_contributions && site_wagers == cycle.wager_count,
+            "cycle {} site totals {}/{} do not match cycle totals {}/{}",
+            play.cycle_id,
+            site_contributions,
+            site_wagers,
+            cycle.total_contributions,
+            cycle.wager_count
+        );
+    }
+    ensure!(
+        wins == final_cycle - 1 && (1..final_cycle).all(|c| wins_by_cycle.contains_key(&c)),
+        "{wins} wins recorded but the pool is on cycle {final_cycle}"
+    );
+    ensure!(
+        pool.config.seed * (wins + 1) + contributed == paid_out + final_value,
+        "pool value does not add up: seeded {} + contributed {} != paid {} + remaining {}",
+        pool.config.seed * (wins + 1),
+        contributed,
+        paid_out,
+        final_value
+    );
+
+    println!("ok: exactly one winner per cycle, all contributions accounted for per site");
+    Ok(())
+}
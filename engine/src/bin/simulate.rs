@@ -0,0 +1,288 @@
+//! Plays synthetic wagers through `JackpotService` against an in-memory pool, with seeded
+//! randomness so runs are reproducible, and prints hit size, hit frequency and RTP
+//! statistics as JSON.
+//!
+//! ```sh
+//! cargo run --release -p engine --bin simulate -- --wagers 10000000 --hit-probability 0.0001
+//! cargo run --release -p engine --bin simulate -- --pool-file pool.json --output sim.json
+//! ```
+//!
+//! `--pool-file` takes a pool configuration in the same JSON shape as the admin API.
+
+use std::{collections::BTreeMap, fs::File, io, path::PathBuf, sync::Arc};
+
+use anyhow::{Context, ensure};
+use clap::Parser;
+use engine::{
+    domain::models::{NewPool, PoolConfig, WagerRequest},
+    pool_config::{PoolConfigStore, in_memory::InMemoryPoolConfigStore},
+    pool_store::in_memory::InMemoryPoolStore,
+    services::{jackpot::JackpotService, rolls::SeededRolls},
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde::Serialize;
+use uuid::Uuid;
+
+const POOL_ID: &str = "simulation";
+
+#[derive(Parser)]
+struct Args {
+    /// Wagers to play.
+    #[arg(long, default_value_t = 1_000_000)]
+    wagers: u64,
+    /// Seed for both the win rolls and the synthetic wagers.
+    #[arg(long, default_value_t = 1)]
+    seed: u64,
+    /// Pool configuration as JSON; overrides the pool flags below.
+    #[arg(long)]
+    pool_file: Option<PathBuf>,
+    /// Amount every cycle starts from.
+    #[arg(long, default_value_t = 100_000)]
+    pool_seed: u64,
+    #[arg(long, default_value_t = 0.01)]
+    contribution_rate: f64,
+    #[arg(long, default_value_t = 0.0001)]
+    hit_probability: f64,
+    /// Sites the wagers are spread over evenly, numbered from 1.
+    #[arg(long, default_value_t = 1)]
+    sites: i32,
+    /// Smallest wager amount; amounts are drawn uniformly from the range.
+    #[arg(long, default_value_t = 100)]
+    min_amount: u64,
+    #[arg(long, default_value_t = 10_000)]
+    max_amount: u64,
+    /// Buckets in each histogram.
+    #[arg(long, default_value_t = 20)]
+    buckets: usize,
+    /// Where to write the JSON report; stdout if not set.
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    wagers: u64,
+    seed: u64,
+    pool: PoolConfig,
+    total_wagered: u64,
+    total_contributed: u64,
+    total_paid: u64,
+    /// Seeds put into the pool: one per won cycle plus the cycle still running.
+    seed_funding: u64,
+    final_pool_value: u64,
+    hits: u64,
+    /// Share of turnover that goes into the pool.
+    effective_contribution_rate: f64,
+    /// Share of turnover paid back as jackpot wins, seeds included.
+    rtp_contribution: f64,
+    expected_wagers_per_hit: f64,
+    /// Wagers per won cycle; the cycle still running is not counted.
+    wagers_per_hit: Distribution,
+    hit_size: Distribution,
+    sites: Vec<SiteReport>,
+}
+
+#[derive(Serialize)]
+struct Distribution {
+    count: usize,
+    mean: f64,
+    min: u64,
+    max: u64,
+    p50: u64,
+    p90: u64,
+    p99: u64,
+    histogram: Vec<Bucket>,
+}
+
+#[derive(Serialize)]
+struct Bucket {
+    /// Inclusive lower bound.
+    from: u64,
+    /// Exclusive upper bound, inclusive for the last bucket.
+    to: u64,
+    count: usize,
+}
+
+#[derive(Default, Serialize)]
+struct SiteReport {
+    site_id: i32,
+    wagers: u64,
+    wagered: u64,
+    contributed: u64,
+    hits: u64,
+    paid: u64,
+}
+
+impl Distribution {
+    fn new(mut values: Vec<u64>, buckets: usize) -> Self {
+        values.sort_unstable();
+        let (Some(&min), Some(&max)) = (values.first(), values.last()) else {
+            return Self {
+                count: 0,
+                mean: 0.0,
+                min: 0,
+                max: 0,
+                p50: 0,
+                p90: 0,
+                p99: 0,
+                histogram: Vec::new(),
+            };
+        };
+        let percentile = |p: f64| values[((values.len() as f64 * p).ceil() as usize).max(1) - 1];
+
+        let width = ((max - min) / buckets as u64 + 1).max(1);
+        let mut histogram: Vec<Bucket> = (0..buckets as u64)
+            .map(|i| Bucket {
+                from: min + i * width,
+                to: min + (i + 1) * width,
+                count: 0,
+            })
+            .take_while(|bucket| bucket.from <= max)
+            .collect();
+        for value in &values {
+            histogram[((value - min) / width) as usize].count += 1;
+        }
+        if let Some(last) = histogram.last_mut() {
+            last.to = max;
+        }
+
+        Self {
+            count: values.len(),
+            mean: values.iter().map(|&v| v as f64).sum::<f64>() / values.len() as f64,
+            min,
+            max,
+            p50: percentile(0.50),
+            p90: percentile(0.90),
+            p99: percentile(0.99),
+            histogram,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    ensure!(args.sites >= 1, "--sites must be at least 1");
+    ensure!(args.buckets >= 1, "--buckets must be at least 1");
+    ensure!(
+        args.min_amount <= args.max_amount,
+        "--min-amount must not exceed --max-amount"
+    );
+
+    let config = match &args.pool_file {
+        Some(path) => serde_json::from_reader(
+            File::open(path).with_context(|| format!("Failed to open {}", path.display()))?,
+        )
+        .with_context(|| format!("Failed to parse pool configuration {}", path.display()))?,
+        None => PoolConfig {
+            site_ids: (1..=args.sites).collect(),
+            seed: args.pool_seed,
+            contribution_rate: args.contribution_rate,
+            site_rates: BTreeMap::new(),
+            hit_probability: args.hit_probability,
+        },
+    };
+    config.validate().map_err(anyhow::Error::msg)?;
+
+    let pool_config = Arc::new(InMemoryPoolConfigStore::new());
+    pool_config
+        .create_pool(&NewPool {
+            id: POOL_ID.to_string(),
+            config: config.clone(),
+        })
+        .await?;
+    let pool_store = Arc::new(InMemoryPoolStore::new());
+    let jackpot_service = JackpotService::new(
+        pool_store.clone(),
+        pool_config,
+        Arc::new(SeededRolls::new(args.seed)),
+    );
+    // Wagers draw from their own stream so changing the amounts leaves the rolls alone.
+    let mut wager_rng = StdRng::seed_from_u64(args.seed.wrapping_add(1));
+
+    let mut sites: BTreeMap<i32, SiteReport> = config
+        .site_ids
+        .iter()
+        .map(|&site_id| {
+            let site = SiteReport {
+                site_id,
+                ..Default::default()
+            };
+            (site_id, site)
+        })
+        .collect();
+    let mut hit_sizes = Vec::new();
+    let mut cycle_lengths = Vec::new();
+    let pool = jackpot_service.pool_for_site(config.site_ids[0]).await?;
+
+    for _ in 0..args.wagers {
+        let request = WagerRequest {
+            id: None,
+            amount: wager_rng.random_range(args.min_amount..=args.max_amount),
+            site_id: config.site_ids[wager_rng.random_range(0..config.site_ids.len())],
+            user_id: 0,
+            game_id: 0,
+            currency: None,
+            received_at: None,
+            cheat_code: None,
+        };
+        let play = jackpot_service
+            .play(&pool, Uuid::new_v4(), &request)
+            .await?;
+
+        let site = sites.entry(request.site_id).or_default();
+        site.wagers += 1;
+        site.wagered += request.amount;
+        site.contributed += play.contribution;
+        if let Some(cycle) = &play.completed_cycle {
+            site.hits += 1;
+            site.paid += play.pool_value;
+            hit_sizes.push(play.pool_value);
+            cycle_lengths.push(cycle.wager_count);
+        }
+    }
+
+    let total_wagered: u64 = sites.values().map(|site| site.wagered).sum();
+    let total_paid: u64 = hit_sizes.iter().sum();
+    let hits = hit_sizes.len() as u64;
+    let report = Report {
+        wagers: args.wagers,
+        seed: args.seed,
+        total_wagered,
+        total_contributed: sites.values().map(|site| site.contributed).sum(),
+        total_paid,
+        seed_funding: config.seed * (hits + 1),
+        final_pool_value: pool_store.value(POOL_ID).unwrap_or(config.seed),
+        hits,
+        effective_contribution_rate: ratio(
+            sites.values().map(|site| site.contributed).sum(),
+            total_wagered,
+        ),
+        rtp_contribution: ratio(total_paid, total_wagered),
+        expected_wagers_per_hit: 1.0 / config.hit_probability,
+        wagers_per_hit: Distribution::new(cycle_lengths, args.buckets),
+        hit_size: Distribution::new(hit_sizes, args.buckets),
+        sites: sites.into_values().collect(),
+        pool: config,
+    };
+
+    match &args.output {
+        Some(path) => serde_json::to_writer_pretty(
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?,
+            &report,
+        )?,
+        None => {
+            serde_json::to_writer_pretty(io::stdout().lock(), &report)?;
+            println!();
+        }
+    }
+    Ok(())
+}
+
+fn ratio(part: u64, whole: u64) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
@@ -1,4 +1,6 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -8,6 +10,12 @@ pub struct WagerRequest {
     pub site_id: i32,
     pub user_id: i32,
     pub game_id: i32,
+    /// ISO 4217 code of `amount`, as given by the operator.
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// When the gateway accepted the wager.
+    #[serde(default)]
+    pub received_at: Option<DateTime<Utc>>,
 
     pub cheat_code: Option<String>,
 }
@@ -20,9 +28,213 @@ pub struct WagerResponse {
     pub amount: u64,
 
     pub receipt_id: Option<String>,
+    /// Why the wager was rejected without being played, e.g. `pool_paused`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
+impl WagerResponse {
+    pub fn rejected(wager_id: Uuid, amount: u64, code: &str) -> Self {
+        Self {
+            wager_id,
+            status: "rejected".to_string(),
+            amount,
+            receipt_id: None,
+            error: Some(code.to_string()),
+        }
+    }
+}
+
+/// Storage's answer to a winning wager: `stored`, `duplicate` for a redelivery, or
+/// `conflict` if it already holds a different wager under the same id.
 #[derive(serde::Deserialize)]
 pub struct ReceiptResponse {
-    pub receipt_id: String,
+    pub status: String,
+    /// Missing for wagers stored before receipts were recorded.
+    pub receipt_id: Option<String>,
+}
+
+/// Published to the events exchange whenever a wager wins the jackpot.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct JackpotWonEvent {
+    pub event_id: Uuid,
+    pub wager_id: Uuid,
+    pub site_id: i32,
+    pub user_id: i32,
+    pub game_id: i32,
+    pub wager_amount: u64,
+    pub pool_id: String,
+    pub cycle_id: u64,
+    pub cycle_started_at: DateTime<Utc>,
+    pub seed: u64,
+    pub total_contributions: u64,
+    pub wager_count: u64,
+    pub win_amount: u64,
+    pub trigger_roll: f64,
+    pub won_at: DateTime<Utc>,
+    /// What each site put into the cycle, so storage can settle network pools.
+    pub site_contributions: Vec<SiteContribution>,
+}
+
+/// One site's share of a pool cycle.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SiteContribution {
+    pub site_id: i32,
+    pub contributions: u64,
+    pub wager_count: u64,
+}
+
+/// A wager as handed to storage, stamped with the pool configuration it was played under.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ProcessedWager {
+    pub id: Uuid,
+    pub site_id: i32,
+    pub user_id: i32,
+    pub game_id: i32,
+    pub amount: u64,
+    pub pool_id: String,
+    pub pool_version: i32,
+    /// Cycle of the pool the wager played in.
+    pub cycle_id: u64,
+    /// What the wager added to the pool.
+    pub contribution: u64,
+    /// The jackpot paid out if the wager won, otherwise 0.
+    pub win_amount: u64,
+    pub outcome: WagerOutcome,
+    pub currency: Option<String>,
+    /// When the gateway accepted the wager, or the engine did if the gateway did not say.
+    pub received_at: DateTime<Utc>,
+    /// When the engine settled the wager against its pool.
+    pub processed_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WagerOutcome {
+    Won,
+    Lost,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PoolStatus {
+    /// Takes wagers and pays out.
+    Active,
+    /// Frozen, e.g. during an incident: keeps its value but rejects contributions until
+    /// resumed.
+    Paused,
+    /// Takes no new wagers; wagers already admitted still finish.
+    Closing,
+    /// Permanently closed once its in-flight wagers have drained.
+    Closed,
+}
+
+impl PoolStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PoolStatus::Active => "active",
+            PoolStatus::Paused => "paused",
+            PoolStatus::Closing => "closing",
+            PoolStatus::Closed => "closed",
+        }
+    }
+}
+
+impl TryFrom<String> for PoolStatus {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        match s.as_str() {
+            "active" => Ok(Self::Active),
+            "paused" => Ok(Self::Paused),
+            "closing" => Ok(Self::Closing),
+            "closed" => Ok(Self::Closed),
+            other => Err(format!("{} is not a known pool status", other)),
+        }
+    }
+}
+
+/// The tunable part of a pool. Every change is stored as a new version.
+///
+/// A pool with several sites is a network pool: all of them feed and can win the same
+/// jackpot, each at its own rate if `site_rates` says so.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PoolConfig {
+    /// Sites whose wagers contribute to (and can win) this pool.
+    pub site_ids: Vec<i32>,
+    /// Value the pool starts each cycle with, in minor currency units.
+    pub seed: u64,
+    /// Share of each wager added to the pool, e.g. `0.01` for 1%.
+    pub contribution_rate: f64,
+    /// Per-site overrides of `contribution_rate`.
+    #[serde(default)]
+    pub site_rates: BTreeMap<i32, f64>,
+    /// Chance that a single wager wins the pool.
+    pub hit_probability: f64,
+}
+
+impl PoolConfig {
+    /// Share of a wager from `site_id` that is added to the pool.
+    pub fn rate_for_site(&self, site_id: i32) -> f64 {
+        self.site_rates
+            .get(&site_id)
+            .copied()
+            .unwrap_or(self.contribution_rate)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.site_ids.is_empty() {
+            return Err("site_ids must not be empty".into());
+        }
+        if !(0.0..=1.0).contains(&self.contribution_rate) {
+            return Err("contribution_rate must be between 0 and 1".into());
+        }
+        for (site_id, rate) in &self.site_rates {
+            if !self.site_ids.contains(site_id) {
+                return Err(format!(
+                    "site_rates has site {site_id}, which is not in site_ids"
+                ));
+            }
+            if !(0.0..=1.0).contains(rate) {
+                return Err(format!(
+                    "site_rates for site {site_id} must be between 0 and 1"
+                ));
+            }
+        }
+        if !(self.hit_probability > 0.0 && self.hit_probability <= 1.0) {
+            return Err("hit_probability must be greater than 0 and at most 1".into());
+        }
+        if self.seed > i64::MAX as u64 {
+            return Err("seed is too large".into());
+        }
+        Ok(())
+    }
+}
+
+/// A pool with the configuration of its current version.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Pool {
+    pub id: String,
+    pub status: PoolStatus,
+    pub version: i32,
+    #[serde(flatten)]
+    pub config: PoolConfig,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One stored configuration of a pool.
+#[derive(Clone, Debug, Serialize)]
+pub struct PoolVersion {
+    pub pool_id: String,
+    pub version: i32,
+    #[serde(flatten)]
+    pub config: PoolConfig,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewPool {
+    pub id: String,
+    #[serde(flatten)]
+    pub config: PoolConfig,
 }
@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use serde::Serialize;
+use tracing::error;
+use warp::{
+    Rejection, Reply,
+    http::StatusCode,
+    reply::{WithStatus, json, with_status},
+};
+
+use crate::{
+    domain::models::{NewPool, PoolConfig},
+    services::pool_admin::{PoolAdminError, PoolAdminService},
+};
+
+pub async fn list_pools(pool_admin: Arc<PoolAdminService>) -> Result<impl Reply, Rejection> {
+    Ok(reply(pool_admin.list_pools().await, StatusCode::OK))
+}
+
+pub async fn get_pool(
+    id: String,
+    pool_admin: Arc<PoolAdminService>,
+) -> Result<impl Reply, Rejection> {
+    Ok(reply(pool_admin.get_pool(&id).await, StatusCode::OK))
+}
+
+pub async fn list_versions(
+    id: String,
+    pool_admin: Arc<PoolAdminService>,
+) -> Result<impl Reply, Rejection> {
+    Ok(reply(pool_admin.list_versions(&id).await, StatusCode::OK))
+}
+
+pub async fn create_pool(
+    pool: NewPool,
+    pool_admin: Arc<PoolAdminService>,
+) -> Result<impl Reply, Rejection> {
+    Ok(reply(
+        pool_admin.create_pool(pool).await,
+        StatusCode::CREATED,
+    ))
+}
+
+pub async fn update_pool(
+    id: String,
+    config: PoolConfig,
+    pool_admin: Arc<PoolAdminService>,
+) -> Result<impl Reply, Rejection> {
+    Ok(reply(
+        pool_admin.update_pool(&id, config).await,
+        StatusCode::OK,
+    ))
+}
+
+pub async fn pause_pool(
+    id: String,
+    pool_admin: Arc<PoolAdminService>,
+) -> Result<impl Reply, Rejection> {
+    Ok(reply(pool_admin.pause(&id).await, StatusCode::OK))
+}
+
+pub async fn resume_pool(
+    id: String,
+    pool_admin: Arc<PoolAdminService>,
+) -> Result<impl Reply, Rejection> {
+    Ok(reply(pool_admin.resume(&id).await, StatusCode::OK))
+}
+
+pub async fn close_pool(
+    id: String,
+    pool_admin: Arc<PoolAdminService>,
+) -> Result<impl Reply, Rejection> {
+    Ok(reply(pool_admin.close(&id).await, StatusCode::OK))
+}
+
+fn reply<T: Serialize>(
+    result: Result<T, PoolAdminError>,
+    success: StatusCode,
+) -> WithStatus<warp::reply::Json> {
+    match result {
+        Ok(body) => with_status(json(&body), success),
+        Err(PoolAdminError::Other(e)) => {
+            error!("Pool admin request failed: {:?}", e);
+            with_status(
+                json(&"Failed to process pool request"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        }
+        Err(e) => {
+            let status = match e {
+                PoolAdminError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+                PoolAdminError::NotFound(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::CONFLICT,
+            };
+            with_status(json(&e.to_string()), status)
+        }
+    }
+}
@@ -1,6 +1,10 @@
 pub mod configuration;
 pub mod domain;
+pub mod handlers;
 pub mod messaging;
+pub mod metrics;
+pub mod pool_config;
+pub mod pool_store;
 pub mod server;
 pub mod services;
 pub mod telemetry;
@@ -4,27 +4,68 @@ use engine::{
         connection::RabbitConnection, consumer_client::ConsumerClient,
         publish_client::PublishClient, rpc_client::RpcClient,
     },
+    pool_config::{
+        PoolConfigStore, postgres_store::PostgresPoolConfigStore,
+        redis_cache::RedisCachedPoolConfigStore,
+    },
+    pool_store::redis_store::RedisPoolStore,
     server,
-    services::{jackpot::JackpotService, processor::JackpotProcessor},
-    telemetry::{get_subscriber, init_subscriber},
+    services::{
+        jackpot::JackpotService, pool_admin::PoolAdminService, processor::JackpotProcessor,
+        rolls::ThreadRolls,
+    },
+    telemetry::{get_subscriber, init_subscriber, shutdown_tracer_provider},
 };
 use lapin::ExchangeKind;
 use secrecy::ExposeSecret;
 use std::{
     fmt::{Debug, Display},
     sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    signal::unix::{SignalKind, signal},
+    task::JoinError,
 };
-use tokio::task::JoinError;
+use tokio_util::sync::CancellationToken;
+
+/// How often closing pools are checked for having drained.
+const CLOSE_SWEEP_INTERVAL: Duration = Duration::from_secs(5);
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let subscriber = get_subscriber("engine".into(), "info".into(), std::io::stdout);
-    init_subscriber(subscriber);
-
     let configuration = get_configuration().expect("Failed to read configuration.");
 
-    let jackpot_service =
-        Arc::new(JackpotService::new(&configuration.redis.uri.expose_secret()).await?);
+    let subscriber = get_subscriber(
+        "engine".into(),
+        "info".into(),
+        std::io::stdout,
+        configuration.telemetry.otlp_endpoint.as_deref(),
+    );
+    init_subscriber(subscriber);
+
+    let pg_pool = sqlx::postgres::PgPoolOptions::new()
+        .max_connections(5)
+        .connect_lazy(&configuration.postgres.build_url())?;
+    let pool_config: Arc<dyn PoolConfigStore> = Arc::new(
+        RedisCachedPoolConfigStore::new(
+            Arc::new(PostgresPoolConfigStore::new(pg_pool)),
+            configuration.redis.uri.expose_secret(),
+            Duration::from_secs(configuration.jackpot.pool_cache_ttl_secs),
+        )
+        .await?,
+    );
+    let pool_store = Arc::new(RedisPoolStore::new(configuration.redis.uri.expose_secret()).await?);
+    let jackpot_service = Arc::new(JackpotService::new(
+        pool_store,
+        pool_config.clone(),
+        Arc::new(ThreadRolls),
+    ));
+    let pool_admin = Arc::new(PoolAdminService::new(pool_config));
+    pool_admin.clone().spawn_close_sweeper(
+        CLOSE_SWEEP_INTERVAL,
+        Duration::from_secs(configuration.jackpot.close_drain_secs),
+    );
 
     // Set up RabbitMQ connections
     let gateway_connection =
@@ -39,10 +80,20 @@ async fn main() -> anyhow::Result<()> {
     let publish_client =
         Arc::new(PublishClient::new(&storage_connection, "storage", ExchangeKind::Direct).await?);
 
+    let events_client = Arc::new(
+        PublishClient::new(
+            &storage_connection,
+            &configuration.rabbitmq.events_exchange,
+            ExchangeKind::Fanout,
+        )
+        .await?,
+    );
+
     let processor = Arc::new(JackpotProcessor {
         jackpot_service,
         storage_rpc_client,
         publish_client,
+        events_client,
     });
 
     let consumer_client = ConsumerClient::new(
@@ -51,23 +102,34 @@ async fn main() -> anyhow::Result<()> {
         ExchangeKind::Direct,
         "gateway_queue",
         "",
+        &configuration.consumer,
         processor.clone(),
     )
     .await?;
 
-    let gateway_consumer = tokio::spawn(async move { consumer_client.start_consuming().await });
+    let shutdown = CancellationToken::new();
+    let drain_timeout = Duration::from_secs(configuration.application.shutdown_timeout_secs);
+    let mut gateway_consumer = {
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            consumer_client
+                .start_consuming(shutdown, drain_timeout)
+                .await
+        })
+    };
 
     let server_task = tokio::spawn(
         server::start_server(
             configuration.application,
             gateway_connection.clone(),
             storage_connection.clone(),
+            pool_admin,
         )
         .await?,
     );
 
     tokio::select! {
-        o = gateway_consumer => report_exit("Gateway Consumer", o),
+        o = &mut gateway_consumer => report_exit("Gateway Consumer", o),
         o = server_task => {
                     match o {
                         Ok(()) => tracing::info!("Server has exited"),
@@ -78,11 +140,28 @@ async fn main() -> anyhow::Result<()> {
                         ),
                     }
                 },
+        () = shutdown_signal() => {
+            tracing::info!("Shutdown signal received, draining in-flight wagers");
+            shutdown.cancel();
+            report_exit("Gateway Consumer", gateway_consumer.await);
+        }
     }
 
+    gateway_connection.close().await;
+    storage_connection.close().await;
+    shutdown_tracer_provider();
     Ok(())
 }
 
+/// Resolves on SIGTERM (sent by Docker and Kubernetes) or Ctrl-C.
+async fn shutdown_signal() {
+    let mut terminate = signal(SignalKind::terminate()).expect("Failed to install SIGTERM handler");
+    tokio::select! {
+        _ = terminate.recv() => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
+}
+
 fn report_exit(task_name: &str, outcome: Result<Result<(), impl Debug + Display>, JoinError>) {
     match outcome {
         Ok(Ok(())) => {
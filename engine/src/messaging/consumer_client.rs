@@ -4,21 +4,41 @@ use lapin::{
     Channel, ExchangeKind,
     message::Delivery,
     options::{
-        BasicAckOptions, BasicConsumeOptions, BasicPublishOptions, ExchangeDeclareOptions,
-        QueueBindOptions, QueueDeclareOptions,
+        BasicAckOptions, BasicCancelOptions, BasicConsumeOptions, BasicNackOptions,
+        BasicPublishOptions, BasicQosOptions, ExchangeDeclareOptions, QueueBindOptions,
+        QueueDeclareOptions,
     },
-    types::FieldTable,
+    types::{AMQPValue, FieldTable},
 };
-use std::sync::Arc;
-use tracing::{error, info};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::Semaphore;
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+use tracing::{Instrument, error, info, info_span, warn};
+use uuid::Uuid;
 
-use super::connection::RabbitConnection;
-use crate::domain::models::WagerRequest;
-use crate::services::processor::JackpotProcessor;
+use super::{connection::RabbitConnection, trace_context};
+use crate::configuration::ConsumerSettings;
+use crate::domain::models::{WagerRequest, WagerResponse};
+use crate::metrics::{QUEUE_DEPTH, WAGERS_PROCESSED};
+use crate::services::{jackpot::JackpotError, processor::JackpotProcessor};
+
+const CONSUMER_TAG: &str = "jackpot_engine_consumer";
+const QUEUE_DEPTH_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+/// How many times a delivery has been put back on the queue after a transient failure.
+const RETRY_COUNT_HEADER: &str = "x-retry-count";
 
 pub struct ConsumerClient {
     channel: Arc<Channel>,
+    // Only used to sample the queue depth: a failed passive declare closes its channel,
+    // which must not take the consumer's deliveries and replies down with it.
+    sampler_channel: Arc<Channel>,
     queue_name: String,
+    retry_queue: String,
+    dead_letter_queue: String,
+    max_retries: u32,
+    retry_backoff: Duration,
+    tasks: TaskTracker,
+    workers: Arc<Semaphore>,
     processor: Arc<JackpotProcessor>,
 }
 
@@ -30,6 +50,7 @@ impl ConsumerClient {
         exchange_kind: ExchangeKind,
         queue_name: &str,
         routing_key: &str,
+        settings: &ConsumerSettings,
         processor: Arc<JackpotProcessor>,
     ) -> anyhow::Result<Self> {
         let channel = Arc::new(connection.create_channel().await?);
@@ -67,39 +88,149 @@ impl ConsumerClient {
             .await
             .context("Failed to bind queue")?;
 
+        // Wagers waiting out their backoff sit here until their expiration moves them back to
+        // the wager queue, so no worker or prefetch slot is held while they wait
+        let retry_queue = format!("{queue_name}.retry");
+        let mut retry_arguments = FieldTable::default();
+        retry_arguments.insert(
+            "x-dead-letter-exchange".into(),
+            AMQPValue::LongString("".into()),
+        );
+        retry_arguments.insert(
+            "x-dead-letter-routing-key".into(),
+            AMQPValue::LongString(queue_name.into()),
+        );
+        channel
+            .queue_declare(
+                &retry_queue,
+                QueueDeclareOptions {
+                    durable: true,
+                    ..Default::default()
+                },
+                retry_arguments,
+            )
+            .await
+            .context("Failed to declare retry queue")?;
+
+        // Wagers that keep failing are parked here for inspection instead of being dropped
+        let dead_letter_queue = format!("{queue_name}.dead");
+        channel
+            .queue_declare(
+                &dead_letter_queue,
+                QueueDeclareOptions {
+                    durable: true,
+                    ..Default::default()
+                },
+                FieldTable::default(),
+            )
+            .await
+            .context("Failed to declare dead-letter queue")?;
+
+        // Cap unacked deliveries so a backlog stays in the broker rather than in memory
+        channel
+            .basic_qos(settings.prefetch_count, BasicQosOptions::default())
+            .await
+            .context("Failed to set prefetch count")?;
+
+        let sampler_channel = Arc::new(connection.create_channel().await?);
+
         Ok(Self {
             channel,
+            sampler_channel,
             queue_name: queue_name.to_string(),
+            retry_queue,
+            dead_letter_queue,
+            max_retries: settings.max_retries,
+            retry_backoff: Duration::from_millis(settings.retry_backoff_ms),
+            tasks: TaskTracker::new(),
+            workers: Arc::new(Semaphore::new(settings.max_concurrency.max(1))),
             processor,
         })
     }
 
-    /// Starts consuming messages from the queue, processing each one in a spawned task.
-    pub async fn start_consuming(&self) -> anyhow::Result<()> {
+    /// Consumes messages, processing each in a spawned task once a worker is free, until
+    /// `shutdown` is cancelled.
+    /// The consumer is then cancelled so the broker stops delivering, and in-flight deliveries
+    /// get up to `drain_timeout` to finish and be acked before the channel is closed.
+    pub async fn start_consuming(
+        &self,
+        shutdown: CancellationToken,
+        drain_timeout: Duration,
+    ) -> anyhow::Result<()> {
         info!("Starting consumer for queue: {}", self.queue_name);
 
         let mut consumer = self
             .channel
             .basic_consume(
                 &self.queue_name,
-                "jackpot_engine_consumer",
+                CONSUMER_TAG,
                 BasicConsumeOptions::default(),
                 FieldTable::default(),
             )
             .await
             .context("Failed to start consumer")?;
 
-        while let Some(delivery) = consumer.next().await {
+        self.spawn_queue_depth_sampler(shutdown.clone());
+
+        loop {
+            let permit = tokio::select! {
+                permit = self.workers.clone().acquire_owned() => {
+                    permit.context("Worker pool closed")?
+                }
+                () = shutdown.cancelled() => break,
+            };
+            let delivery = tokio::select! {
+                delivery = consumer.next() => delivery,
+                () = shutdown.cancelled() => break,
+            };
+            let Some(delivery) = delivery else {
+                break;
+            };
             if let Ok(delivery) = delivery {
                 let client = self.clone();
-                tokio::spawn(async move {
-                    client.process_delivery(delivery).await;
-                });
+                let span = info_span!("consume", queue = %self.queue_name);
+                trace_context::set_parent_from_properties(&span, &delivery.properties);
+                self.tasks.spawn(
+                    async move {
+                        client.process_delivery(delivery).await;
+                        drop(permit);
+                    }
+                    .instrument(span),
+                );
             }
         }
+
+        self.drain(drain_timeout).await;
         Ok(())
     }
 
+    async fn drain(&self, drain_timeout: Duration) {
+        info!("Stopping consumer for queue: {}", self.queue_name);
+        if let Err(e) = self
+            .channel
+            .basic_cancel(CONSUMER_TAG, BasicCancelOptions::default())
+            .await
+        {
+            warn!("Failed to cancel consumer: {:?}", e);
+        }
+
+        self.tasks.close();
+        if tokio::time::timeout(drain_timeout, self.tasks.wait())
+            .await
+            .is_err()
+        {
+            // Unacked deliveries are requeued by the broker once the channel closes.
+            warn!(
+                in_flight = self.tasks.len(),
+                "Drain timeout elapsed, abandoning in-flight deliveries"
+            );
+        }
+
+        if let Err(e) = self.channel.close(200, "shutting down").await {
+            warn!("Failed to close channel: {:?}", e);
+        }
+    }
+
     /// Processes a single delivery, handling deserialization, processing, response sending, and acknowledgment.
     async fn process_delivery(&self, delivery: Delivery) {
         info!("Processing delivery");
@@ -108,53 +239,218 @@ impl ConsumerClient {
             Ok(req) => req,
             Err(e) => {
                 error!("Failed to deserialize request: {:?}", e);
+                reject(&delivery).await;
                 return;
             }
         };
+        let wager_id = request.id;
+        let amount = request.amount;
 
-        match self.processor.process_wager(request).await {
+        let response = match self.processor.process_wager(request).await {
             Ok(response) => {
                 info!("Wager processed successfully");
-                match serde_json::to_vec(&response) {
-                    Ok(response_bytes) => {
-                        if let Some(reply_to) = delivery.properties.reply_to() {
-                            if let Err(e) = self
-                                .channel
-                                .basic_publish(
-                                    "",
-                                    reply_to.as_str(),
-                                    BasicPublishOptions::default(),
-                                    &response_bytes,
-                                    lapin::BasicProperties::default().with_correlation_id(
-                                        delivery
-                                            .properties
-                                            .correlation_id()
-                                            .clone()
-                                            .unwrap_or_default(),
-                                    ),
-                                )
-                                .await
-                            {
-                                error!("Failed to send response: {:?}", e);
-                            } else {
-                                info!("Response sent to reply_to queue");
-                            }
-                        }
-                        if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
-                            error!("Failed to acknowledge message: {:?}", e);
-                        } else {
-                            info!("Message acknowledged");
-                        }
-                    }
+                let outcome = if response.status == "true" {
+                    "won"
+                } else {
+                    "lost"
+                };
+                WAGERS_PROCESSED.with_label_values(&[outcome]).inc();
+                response
+            }
+            Err(e) => {
+                let Some(code) = e
+                    .downcast_ref::<JackpotError>()
+                    .and_then(JackpotError::code)
+                else {
+                    WAGERS_PROCESSED.with_label_values(&["error"]).inc();
+                    error!("Failed to process wager: {:?}", e);
+                    self.retry(&delivery, wager_id, amount).await;
+                    return;
+                };
+                // Rejections are final, so the gateway is told why rather than left to
+                // time out.
+                WAGERS_PROCESSED.with_label_values(&["rejected"]).inc();
+                warn!(error = %e, "Wager rejected");
+                WagerResponse::rejected(wager_id.unwrap_or_else(Uuid::new_v4), amount, code)
+            }
+        };
+
+        self.reply(&delivery, &response).await;
+        if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
+            error!("Failed to acknowledge message: {:?}", e);
+        } else {
+            info!("Message acknowledged");
+        }
+    }
+
+    /// Sends `response` to the delivery's `reply_to` queue, if it has one.
+    async fn reply(&self, delivery: &Delivery, response: &WagerResponse) {
+        let Some(reply_to) = delivery.properties.reply_to() else {
+            return;
+        };
+        let response_bytes = match serde_json::to_vec(response) {
+            Ok(response_bytes) => response_bytes,
+            Err(e) => {
+                error!("Failed to serialize response: {:?}", e);
+                return;
+            }
+        };
+        if let Err(e) = self
+            .channel
+            .basic_publish(
+                "",
+                reply_to.as_str(),
+                BasicPublishOptions::default(),
+                &response_bytes,
+                lapin::BasicProperties::default()
+                    .with_correlation_id(
+                        delivery
+                            .properties
+                            .correlation_id()
+                            .clone()
+                            .unwrap_or_default(),
+                    )
+                    .with_headers(trace_context::current_headers()),
+            )
+            .await
+        {
+            error!("Failed to send response: {:?}", e);
+        } else {
+            info!("Response sent to reply_to queue");
+        }
+    }
+
+    /// Parks a delivery that failed for a transient reason on the retry queue, which hands it
+    /// back to the wager queue once its backoff expires. After `max_retries` it is moved to
+    /// the dead-letter queue and the caller is told it failed, rather than left to time out.
+    /// The copy carries the original properties, so a later reply still reaches the caller.
+    async fn retry(&self, delivery: &Delivery, wager_id: Option<Uuid>, amount: u64) {
+        let retries = retry_count(delivery);
+        let mut headers = delivery.properties.headers().clone().unwrap_or_default();
+        headers.insert(RETRY_COUNT_HEADER.into(), AMQPValue::LongUInt(retries + 1));
+        let properties = delivery.properties.clone().with_headers(headers);
+
+        let dead_lettered = retries >= self.max_retries;
+        let (routing_key, properties) = if !dead_lettered {
+            let backoff = self.retry_backoff * 2u32.saturating_pow(retries.min(16));
+            warn!(
+                retries,
+                backoff_ms = backoff.as_millis() as u64,
+                "Requeueing wager for another attempt"
+            );
+            let expiration = backoff.as_millis().to_string();
+            (
+                &self.retry_queue,
+                properties.with_expiration(expiration.into()),
+            )
+        } else {
+            error!(
+                retries,
+                "Wager keeps failing, moving it to the dead-letter queue"
+            );
+            (&self.dead_letter_queue, properties)
+        };
+
+        let published = self
+            .channel
+            .basic_publish(
+                "",
+                routing_key,
+                BasicPublishOptions::default(),
+                &delivery.data,
+                properties,
+            )
+            .await;
+        if published.is_ok() && dead_lettered {
+            let response = WagerResponse::rejected(
+                wager_id.unwrap_or_else(Uuid::new_v4),
+                amount,
+                "processing_failed",
+            );
+            self.reply(delivery, &response).await;
+        }
+
+        // If the copy can't be published the original goes back instead, so it is not lost.
+        let outcome = match published {
+            Ok(_) => delivery.ack(BasicAckOptions::default()).await,
+            Err(e) => {
+                error!("Failed to republish wager for retry: {:?}", e);
+                let options = BasicNackOptions {
+                    requeue: true,
+                    ..Default::default()
+                };
+                delivery.nack(options).await
+            }
+        };
+        if let Err(e) = outcome {
+            error!("Failed to settle message after retry: {:?}", e);
+        }
+    }
+
+    /// Periodically samples the number of ready messages in the queue as a consumer lag signal,
+    /// until `shutdown` is cancelled.
+    fn spawn_queue_depth_sampler(&self, shutdown: CancellationToken) {
+        let channel = self.sampler_channel.clone();
+        let queue_name = self.queue_name.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(QUEUE_DEPTH_SAMPLE_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    () = shutdown.cancelled() => break,
+                }
+                match channel
+                    .queue_declare(
+                        &queue_name,
+                        QueueDeclareOptions {
+                            passive: true,
+                            ..Default::default()
+                        },
+                        FieldTable::default(),
+                    )
+                    .await
+                {
+                    Ok(queue) => QUEUE_DEPTH
+                        .with_label_values(&[queue_name.as_str()])
+                        .set(queue.message_count() as i64),
                     Err(e) => {
-                        error!("Failed to serialize response: {:?}", e);
+                        warn!("Failed to sample depth of queue {}: {:?}", queue_name, e);
+                        if !channel.status().connected() {
+                            warn!("Queue depth sampler channel closed, stopping sampling");
+                            return;
+                        }
                     }
                 }
             }
-            Err(e) => {
-                error!("Failed to process wager: {:?}", e);
+            if let Err(e) = channel.close(200, "shutting down").await {
+                warn!("Failed to close queue depth sampler channel: {:?}", e);
             }
-        }
+        });
+    }
+}
+
+/// Drops a delivery that can't be deserialized. Leaving it unacked would hold one of the
+/// prefetch slots forever, and requeueing would just fail it again.
+async fn reject(delivery: &Delivery) {
+    let options = BasicNackOptions {
+        requeue: false,
+        ..Default::default()
+    };
+    if let Err(e) = delivery.nack(options).await {
+        error!("Failed to reject message: {:?}", e);
+    }
+}
+
+fn retry_count(delivery: &Delivery) -> u32 {
+    let count = delivery
+        .properties
+        .headers()
+        .as_ref()
+        .and_then(|headers| headers.inner().get(RETRY_COUNT_HEADER).cloned());
+    match count {
+        Some(AMQPValue::LongUInt(count)) => count,
+        Some(AMQPValue::LongLongInt(count)) => count.clamp(0, u32::MAX as i64) as u32,
+        _ => 0,
     }
 }
 
@@ -163,7 +459,14 @@ impl Clone for ConsumerClient {
     fn clone(&self) -> Self {
         Self {
             channel: self.channel.clone(),
+            sampler_channel: self.sampler_channel.clone(),
             queue_name: self.queue_name.clone(),
+            retry_queue: self.retry_queue.clone(),
+            dead_letter_queue: self.dead_letter_queue.clone(),
+            max_retries: self.max_retries,
+            retry_backoff: self.retry_backoff,
+            tasks: self.tasks.clone(),
+            workers: self.workers.clone(),
             processor: self.processor.clone(),
         }
     }
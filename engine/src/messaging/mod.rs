@@ -2,3 +2,4 @@ pub mod connection;
 pub mod consumer_client;
 pub mod publish_client;
 pub mod rpc_client;
+pub mod trace_context;
@@ -6,7 +6,7 @@ use lapin::{
 };
 use std::sync::Arc;
 
-use super::connection::RabbitConnection;
+use super::{connection::RabbitConnection, trace_context};
 
 pub struct PublishClient {
     channel: Arc<Channel>,
@@ -40,7 +40,7 @@ impl PublishClient {
 
     /// Publishes a message to the specified exchange with an optional priority.
     pub async fn publish(&self, message: &str, priority: Option<u8>) -> anyhow::Result<()> {
-        let mut props = BasicProperties::default();
+        let mut props = BasicProperties::default().with_headers(trace_context::current_headers());
         if let Some(p) = priority {
             props = props.with_priority(p);
         }
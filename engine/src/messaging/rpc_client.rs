@@ -9,12 +9,17 @@ use lapin::{
     types::FieldTable,
 };
 use serde::de::DeserializeOwned;
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::sync::{Mutex, oneshot};
 use tracing::{error, instrument};
 use uuid::Uuid;
 
-use super::connection::RabbitConnection;
+use super::{connection::RabbitConnection, trace_context};
+use crate::metrics::{RPC_DURATION, RPC_PENDING_REQUESTS};
 
 pub struct RpcClient<Response> {
     channel: Arc<Channel>,
@@ -78,15 +83,46 @@ where
     pub async fn call(&self, message: &str, priority: Option<u8>) -> anyhow::Result<Response> {
         let correlation_id = Uuid::new_v4().to_string();
         let (tx, rx) = oneshot::channel();
+        let started_at = Instant::now();
 
-        self.pending_requests
-            .lock()
-            .await
-            .insert(correlation_id.clone(), tx);
+        {
+            let mut pending_requests = self.pending_requests.lock().await;
+            pending_requests.insert(correlation_id.clone(), tx);
+            RPC_PENDING_REQUESTS.set(pending_requests.len() as i64);
+        }
+
+        let (outcome, result) = match self.publish(&correlation_id, message, priority).await {
+            Ok(()) => match tokio::time::timeout(Duration::from_secs(5), rx).await {
+                Ok(Ok(res)) => ("ok", Ok(res)),
+                Ok(Err(_)) => ("error", Err(anyhow!("Response channel closed"))),
+                Err(_) => ("timeout", Err(anyhow!("RPC timeout"))),
+            },
+            Err(e) => ("error", Err(e)),
+        };
+
+        if result.is_err() {
+            // No reply is coming for this request; don't leave its sender behind.
+            let mut pending_requests = self.pending_requests.lock().await;
+            pending_requests.remove(&correlation_id);
+            RPC_PENDING_REQUESTS.set(pending_requests.len() as i64);
+        }
+        RPC_DURATION
+            .with_label_values(&[outcome])
+            .observe(started_at.elapsed().as_secs_f64());
 
+        result
+    }
+
+    async fn publish(
+        &self,
+        correlation_id: &str,
+        message: &str,
+        priority: Option<u8>,
+    ) -> anyhow::Result<()> {
         let mut props = BasicProperties::default()
-            .with_correlation_id(correlation_id.clone().into())
-            .with_reply_to(self.reply_queue_name.clone().into());
+            .with_correlation_id(correlation_id.into())
+            .with_reply_to(self.reply_queue_name.clone().into())
+            .with_headers(trace_context::current_headers());
 
         if let Some(p) = priority {
             props = props.with_priority(p);
@@ -97,17 +133,12 @@ where
                 &self.exchange_name,
                 "",
                 BasicPublishOptions::default(),
-                &message.as_bytes(),
+                message.as_bytes(),
                 props,
             )
             .await?
             .await?;
-
-        match tokio::time::timeout(Duration::from_secs(5), rx).await {
-            Ok(Ok(res)) => Ok(res),
-            Ok(Err(_)) => Err(anyhow!("Response channel closed")),
-            Err(_) => Err(anyhow!("RPC timeout")),
-        }
+        Ok(())
     }
 
     fn spawn_reply_consumer(
@@ -143,7 +174,13 @@ where
 
                         if let Some(corr_id) = corr_id {
                             let corr_str = corr_id.as_str().to_string();
-                            if let Some(tx) = pending_requests.lock().await.remove(&corr_str) {
+                            let sender = {
+                                let mut pending_requests = pending_requests.lock().await;
+                                let sender = pending_requests.remove(&corr_str);
+                                RPC_PENDING_REQUESTS.set(pending_requests.len() as i64);
+                                sender
+                            };
+                            if let Some(tx) = sender {
                                 match serde_json::from_slice::<Response>(&delivery.data) {
                                     Ok(response) => {
                                         if tx.send(response).is_err() {
@@ -0,0 +1,58 @@
+use std::sync::LazyLock;
+
+use prometheus::{
+    Encoder, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, TextEncoder, exponential_buckets,
+    register_histogram_vec, register_int_counter_vec, register_int_gauge, register_int_gauge_vec,
+};
+
+pub static WAGERS_PROCESSED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "engine_wagers_processed_total",
+        "Wagers processed by the engine, by outcome (won, lost or error)",
+        &["outcome"]
+    )
+    .expect("metric can be registered")
+});
+
+pub static POOL_VALUE: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "engine_pool_value",
+        "Current value of each jackpot pool",
+        &["pool_id"]
+    )
+    .expect("metric can be registered")
+});
+
+pub static RPC_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "engine_rpc_duration_seconds",
+        "Round-trip time of RPCs to storage",
+        &["outcome"],
+        exponential_buckets(0.001, 2.0, 14).expect("valid buckets")
+    )
+    .expect("metric can be registered")
+});
+
+pub static RPC_PENDING_REQUESTS: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!(
+        "engine_rpc_pending_requests",
+        "RPCs waiting for a storage reply"
+    )
+    .expect("metric can be registered")
+});
+
+pub static QUEUE_DEPTH: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "engine_queue_depth",
+        "Messages ready in a consumed queue, sampled periodically",
+        &["queue"]
+    )
+    .expect("metric can be registered")
+});
+
+/// Renders every registered metric in the Prometheus text format.
+pub fn render() -> anyhow::Result<String> {
+    let mut buffer = Vec::new();
+    TextEncoder::new().encode(&prometheus::gather(), &mut buffer)?;
+    Ok(String::from_utf8(buffer)?)
+}
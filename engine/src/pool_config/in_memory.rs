@@ -0,0 +1,106 @@
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::PoolConfigStore;
+use crate::domain::models::{NewPool, Pool, PoolConfig, PoolStatus, PoolVersion};
+
+/// Keeps pool definitions and their versions in process memory. Used by the simulator.
+#[derive(Default)]
+pub struct InMemoryPoolConfigStore {
+    pools: RwLock<Vec<(Pool, Vec<PoolVersion>)>>,
+}
+
+impl InMemoryPoolConfigStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl PoolConfigStore for InMemoryPoolConfigStore {
+    async fn list_pools(&self) -> anyhow::Result<Vec<Pool>> {
+        let pools = self.pools.read().expect("pool config poisoned");
+        Ok(pools.iter().map(|(pool, _)| pool.clone()).collect())
+    }
+
+    async fn find_pool(&self, id: &str) -> anyhow::Result<Option<Pool>> {
+        let pools = self.pools.read().expect("pool config poisoned");
+        Ok(pools
+            .iter()
+            .find(|(pool, _)| pool.id == id)
+            .map(|(pool, _)| pool.clone()))
+    }
+
+    async fn list_versions(&self, id: &str) -> anyhow::Result<Vec<PoolVersion>> {
+        let pools = self.pools.read().expect("pool config poisoned");
+        Ok(pools
+            .iter()
+            .find(|(pool, _)| pool.id == id)
+            .map(|(_, versions)| versions.iter().rev().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn create_pool(&self, new_pool: &NewPool) -> anyhow::Result<Option<Pool>> {
+        let mut pools = self.pools.write().expect("pool config poisoned");
+        if pools.iter().any(|(pool, _)| pool.id == new_pool.id) {
+            return Ok(None);
+        }
+        let now = Utc::now();
+        let pool = Pool {
+            id: new_pool.id.clone(),
+            status: PoolStatus::Active,
+            version: 1,
+            config: new_pool.config.clone(),
+            updated_at: now,
+        };
+        let version = PoolVersion {
+            pool_id: pool.id.clone(),
+            version: 1,
+            config: pool.config.clone(),
+            created_at: now,
+        };
+        pools.push((pool.clone(), vec![version]));
+        Ok(Some(pool))
+    }
+
+    async fn update_pool(&self, id: &str, config: &PoolConfig) -> anyhow::Result<Option<Pool>> {
+        let mut pools = self.pools.write().expect("pool config poisoned");
+        let Some((pool, versions)) = pools.iter_mut().find(|(pool, _)| pool.id == id) else {
+            return Ok(None);
+        };
+        if matches!(pool.status, PoolStatus::Closing | PoolStatus::Closed) {
+            return Ok(None);
+        }
+        let now = Utc::now();
+        pool.version += 1;
+        pool.config = config.clone();
+        pool.updated_at = now;
+        versions.push(PoolVersion {
+            pool_id: pool.id.clone(),
+            version: pool.version,
+            config: config.clone(),
+            created_at: now,
+        });
+        Ok(Some(pool.clone()))
+    }
+
+    async fn transition(
+        &self,
+        id: &str,
+        from: &[PoolStatus],
+        to: PoolStatus,
+    ) -> anyhow::Result<Option<Pool>> {
+        let mut pools = self.pools.write().expect("pool config poisoned");
+        let Some((pool, _)) = pools
+            .iter_mut()
+            .find(|(pool, _)| pool.id == id && from.contains(&pool.status))
+        else {
+            return Ok(None);
+        };
+        pool.status = to;
+        pool.updated_at = Utc::now();
+        Ok(Some(pool.clone()))
+    }
+}
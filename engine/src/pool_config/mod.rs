@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+
+use crate::domain::models::{NewPool, Pool, PoolConfig, PoolStatus, PoolVersion};
+
+pub mod in_memory;
+pub mod postgres_store;
+pub mod redis_cache;
+
+/// Where pool definitions live, so they can change without restarting the engine.
+#[async_trait]
+pub trait PoolConfigStore: Send + Sync {
+    /// Every pool with its current configuration, closed ones included.
+    async fn list_pools(&self) -> anyhow::Result<Vec<Pool>>;
+    async fn find_pool(&self, id: &str) -> anyhow::Result<Option<Pool>>;
+    /// Past and current configurations of a pool, newest first.
+    async fn list_versions(&self, id: &str) -> anyhow::Result<Vec<PoolVersion>>;
+    /// Creates the pool at version 1; returns `None` if the id is taken.
+    async fn create_pool(&self, pool: &NewPool) -> anyhow::Result<Option<Pool>>;
+    /// Stores `config` as the pool's next version; returns `None` if the pool does not
+    /// exist or is closing or closed.
+    async fn update_pool(&self, id: &str, config: &PoolConfig) -> anyhow::Result<Option<Pool>>;
+    /// Moves the pool to `to` if its status is one of `from`; returns `None` if the pool
+    /// does not exist or is in another status.
+    async fn transition(
+        &self,
+        id: &str,
+        from: &[PoolStatus],
+        to: PoolStatus,
+    ) -> anyhow::Result<Option<Pool>>;
+}
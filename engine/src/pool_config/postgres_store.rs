@@ -0,0 +1,231 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, types::Json};
+use std::collections::BTreeMap;
+use tracing::instrument;
+
+use super::PoolConfigStore;
+use crate::domain::models::{NewPool, Pool, PoolConfig, PoolStatus, PoolVersion};
+
+const POOL_SELECT: &str = r#"
+    SELECT p.id, p.status, p.current_version AS version, p.updated_at,
+           v.site_ids, v.seed, v.contribution_rate, v.site_rates, v.hit_probability
+    FROM pools p
+    JOIN pool_versions v ON v.pool_id = p.id AND v.version = p.current_version
+"#;
+
+#[derive(sqlx::FromRow)]
+struct PoolRow {
+    id: String,
+    status: String,
+    version: i32,
+    updated_at: DateTime<Utc>,
+    site_ids: Vec<i32>,
+    seed: i64,
+    contribution_rate: f64,
+    site_rates: Json<BTreeMap<i32, f64>>,
+    hit_probability: f64,
+}
+
+impl TryFrom<PoolRow> for Pool {
+    type Error = anyhow::Error;
+
+    fn try_from(row: PoolRow) -> Result<Self, Self::Error> {
+        Ok(Pool {
+            status: PoolStatus::try_from(row.status).map_err(anyhow::Error::msg)?,
+            id: row.id,
+            version: row.version,
+            config: PoolConfig {
+                site_ids: row.site_ids,
+                seed: row.seed as u64,
+                contribution_rate: row.contribution_rate,
+                site_rates: row.site_rates.0,
+                hit_probability: row.hit_probability,
+            },
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct PoolVersionRow {
+    pool_id: String,
+    version: i32,
+    site_ids: Vec<i32>,
+    seed: i64,
+    contribution_rate: f64,
+    site_rates: Json<BTreeMap<i32, f64>>,
+    hit_probability: f64,
+    created_at: DateTime<Utc>,
+}
+
+impl From<PoolVersionRow> for PoolVersion {
+    fn from(row: PoolVersionRow) -> Self {
+        PoolVersion {
+            pool_id: row.pool_id,
+            version: row.version,
+            config: PoolConfig {
+                site_ids: row.site_ids,
+                seed: row.seed as u64,
+                contribution_rate: row.contribution_rate,
+                site_rates: row.site_rates.0,
+                hit_probability: row.hit_probability,
+            },
+            created_at: row.created_at,
+        }
+    }
+}
+
+pub struct PostgresPoolConfigStore {
+    pool: PgPool,
+}
+
+impl PostgresPoolConfigStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    async fn insert_version(
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: &str,
+        version: i32,
+        config: &PoolConfig,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO pool_versions
+                (pool_id, version, site_ids, seed, contribution_rate, site_rates, hit_probability)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(id)
+        .bind(version)
+        .bind(&config.site_ids)
+        .bind(config.seed as i64)
+        .bind(config.contribution_rate)
+        .bind(Json(&config.site_rates))
+        .bind(config.hit_probability)
+        .execute(&mut **transaction)
+        .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PoolConfigStore for PostgresPoolConfigStore {
+    #[instrument(skip(self))]
+    async fn list_pools(&self) -> anyhow::Result<Vec<Pool>> {
+        sqlx::query_as::<_, PoolRow>(&format!("{POOL_SELECT} ORDER BY p.id"))
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(Pool::try_from)
+            .collect()
+    }
+
+    #[instrument(skip(self))]
+    async fn find_pool(&self, id: &str) -> anyhow::Result<Option<Pool>> {
+        sqlx::query_as::<_, PoolRow>(&format!("{POOL_SELECT} WHERE p.id = $1"))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .map(Pool::try_from)
+            .transpose()
+    }
+
+    #[instrument(skip(self))]
+    async fn list_versions(&self, id: &str) -> anyhow::Result<Vec<PoolVersion>> {
+        let versions = sqlx::query_as::<_, PoolVersionRow>(
+            r#"
+            SELECT pool_id, version, site_ids, seed, contribution_rate, site_rates, hit_probability,
+                   created_at
+            FROM pool_versions
+            WHERE pool_id = $1
+            ORDER BY version DESC
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(versions.into_iter().map(PoolVersion::from).collect())
+    }
+
+    #[instrument(skip(self, pool), fields(pool_id = %pool.id))]
+    async fn create_pool(&self, pool: &NewPool) -> anyhow::Result<Option<Pool>> {
+        let mut transaction = self.pool.begin().await?;
+        let created = sqlx::query(
+            r#"
+            INSERT INTO pools (id, status, current_version)
+            VALUES ($1, $2, 1)
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(&pool.id)
+        .bind(PoolStatus::Active.as_str())
+        .execute(&mut *transaction)
+        .await?
+        .rows_affected()
+            == 1;
+        if !created {
+            return Ok(None);
+        }
+        Self::insert_version(&mut transaction, &pool.id, 1, &pool.config).await?;
+        transaction.commit().await?;
+
+        self.find_pool(&pool.id).await
+    }
+
+    #[instrument(skip(self, config))]
+    async fn update_pool(&self, id: &str, config: &PoolConfig) -> anyhow::Result<Option<Pool>> {
+        let mut transaction = self.pool.begin().await?;
+        let version: Option<i32> = sqlx::query_scalar(
+            r#"
+            UPDATE pools
+            SET current_version = current_version + 1, updated_at = NOW()
+            WHERE id = $1 AND status <> ALL($2)
+            RETURNING current_version
+            "#,
+        )
+        .bind(id)
+        .bind([PoolStatus::Closing.as_str(), PoolStatus::Closed.as_str()])
+        .fetch_optional(&mut *transaction)
+        .await?;
+        let Some(version) = version else {
+            return Ok(None);
+        };
+        Self::insert_version(&mut transaction, id, version, config).await?;
+        transaction.commit().await?;
+
+        self.find_pool(id).await
+    }
+
+    #[instrument(skip(self))]
+    async fn transition(
+        &self,
+        id: &str,
+        from: &[PoolStatus],
+        to: PoolStatus,
+    ) -> anyhow::Result<Option<Pool>> {
+        let from: Vec<&str> = from.iter().map(PoolStatus::as_str).collect();
+        let updated = sqlx::query(
+            r#"
+            UPDATE pools
+            SET status = $2, updated_at = NOW()
+            WHERE id = $1 AND status = ANY($3)
+            "#,
+        )
+        .bind(id)
+        .bind(to.as_str())
+        .bind(from)
+        .execute(&self.pool)
+        .await?
+        .rows_affected()
+            == 1;
+        if !updated {
+            return Ok(None);
+        }
+
+        self.find_pool(id).await
+    }
+}
@@ -0,0 +1,268 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use redis::{AsyncCommands, Script, aio::ConnectionManager};
+
+use super::PoolConfigStore;
+use crate::domain::models::{NewPool, Pool, PoolConfig, PoolStatus, PoolVersion};
+
+const POOLS_KEY: &str = "jackpot:pool_config";
+/// Bumped by every write, so a list read from the inner store before the write is not cached.
+const GENERATION_KEY: &str = "jackpot:pool_config:generation";
+
+// Caches ARGV[2] in KEYS[1] for ARGV[3] seconds, unless the generation in KEYS[2] has moved
+// on from ARGV[1], the generation the list was read at.
+const CACHE_SCRIPT: &str = r#"
+if (redis.call('GET', KEYS[2]) or '0') ~= ARGV[1] then
+    return 0
+end
+redis.call('SET', KEYS[1], ARGV[2], 'EX', ARGV[3])
+return 1
+"#;
+
+/// Serves the pool list from Redis so every wager does not hit Postgres. Writes go to the
+/// inner store, then bump the generation and drop the cached list, so all replicas pick up
+/// changes on their next wager; a list read before a write is never cached after it. Redis
+/// errors fall back to the inner store.
+pub struct RedisCachedPoolConfigStore {
+    inner: Arc<dyn PoolConfigStore>,
+    redis: ConnectionManager,
+    ttl: Duration,
+    cache_script: Script,
+}
+
+impl RedisCachedPoolConfigStore {
+    pub async fn new(
+        inner: Arc<dyn PoolConfigStore>,
+        redis_url: &str,
+        ttl: Duration,
+    ) -> anyhow::Result<Self> {
+        let client = redis::Client::open(redis_url)?;
+        let redis = ConnectionManager::new(client).await?;
+        Ok(Self {
+            inner,
+            redis,
+            ttl,
+            cache_script: Script::new(CACHE_SCRIPT),
+        })
+    }
+
+    async fn cached_pools(&self) -> anyhow::Result<Option<Vec<Pool>>> {
+        let payload: Option<String> = self.redis.clone().get(POOLS_KEY).await?;
+        payload
+            .map(|payload| serde_json::from_str(&payload))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn generation(&self) -> anyhow::Result<u64> {
+        let generation: Option<u64> = self.redis.clone().get(GENERATION_KEY).await?;
+        Ok(generation.unwrap_or(0))
+    }
+
+    /// Caches `pools` if no write happened since `generation` was read.
+    async fn cache_pools(&self, pools: &[Pool], generation: u64) -> anyhow::Result<()> {
+        let payload = serde_json::to_string(pools)?;
+        let _: i64 = self
+            .cache_script
+            .key(POOLS_KEY)
+            .key(GENERATION_KEY)
+            .arg(generation)
+            .arg(payload)
+            .arg(self.ttl.as_secs())
+            .invoke_async(&mut self.redis.clone())
+            .await?;
+        Ok(())
+    }
+
+    async fn invalidate(&self) {
+        let result: redis::RedisResult<()> = redis::pipe()
+            .atomic()
+            .incr(GENERATION_KEY, 1)
+            .ignore()
+            .del(POOLS_KEY)
+            .ignore()
+            .query_async(&mut self.redis.clone())
+            .await;
+        if let Err(e) = result {
+            tracing::warn!(error = %e, "Failed to invalidate cached pool configuration");
+        }
+    }
+}
+
+#[async_trait]
+impl PoolConfigStore for RedisCachedPoolConfigStore {
+    async fn list_pools(&self) -> anyhow::Result<Vec<Pool>> {
+        match self.cached_pools().await {
+            Ok(Some(pools)) => return Ok(pools),
+            Ok(None) => {}
+            Err(e) => tracing::warn!(error = %e, "Failed to read cached pool configuration"),
+        }
+
+        // Read before the inner store, so a write that lands in between is noticed.
+        let generation = self.generation().await;
+        let pools = self.inner.list_pools().await?;
+        let cached = match generation {
+            Ok(generation) => self.cache_pools(&pools, generation).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = cached {
+            tracing::warn!(error = %e, "Failed to cache pool configuration");
+        }
+        Ok(pools)
+    }
+
+    async fn find_pool(&self, id: &str) -> anyhow::Result<Option<Pool>> {
+        self.inner.find_pool(id).await
+    }
+
+    async fn list_versions(&self, id: &str) -> anyhow::Result<Vec<PoolVersion>> {
+        self.inner.list_versions(id).await
+    }
+
+    async fn create_pool(&self, pool: &NewPool) -> anyhow::Result<Option<Pool>> {
+        let created = self.inner.create_pool(pool).await?;
+        self.invalidate().await;
+        Ok(created)
+    }
+
+    async fn update_pool(&self, id: &str, config: &PoolConfig) -> anyhow::Result<Option<Pool>> {
+        let updated = self.inner.update_pool(id, config).await?;
+        self.invalidate().await;
+        Ok(updated)
+    }
+
+    async fn transition(
+        &self,
+        id: &str,
+        from: &[PoolStatus],
+        to: PoolStatus,
+    ) -> anyhow::Result<Option<Pool>> {
+        let updated = self.inner.transition(id, from, to).await?;
+        self.invalidate().await;
+        Ok(updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use async_trait::async_trait;
+    use tokio::sync::{Notify, oneshot};
+
+    use super::{GENERATION_KEY, POOLS_KEY, RedisCachedPoolConfigStore};
+    use crate::{
+        domain::models::{NewPool, Pool, PoolConfig, PoolStatus, PoolVersion},
+        pool_config::{PoolConfigStore, in_memory::InMemoryPoolConfigStore},
+        pool_store::tests::test_pool,
+    };
+
+    /// Holds the next `list_pools` after reading, like a slow Postgres query, until released.
+    struct SlowReads {
+        inner: InMemoryPoolConfigStore,
+        read: tokio::sync::Mutex<Option<oneshot::Sender<()>>>,
+        release: Notify,
+    }
+
+    #[async_trait]
+    impl PoolConfigStore for SlowReads {
+        async fn list_pools(&self) -> anyhow::Result<Vec<Pool>> {
+            let pools = self.inner.list_pools().await?;
+            if let Some(read) = self.read.lock().await.take() {
+                read.send(()).unwrap();
+                self.release.notified().await;
+            }
+            Ok(pools)
+        }
+
+        async fn find_pool(&self, id: &str) -> anyhow::Result<Option<Pool>> {
+            self.inner.find_pool(id).await
+        }
+
+        async fn list_versions(&self, id: &str) -> anyhow::Result<Vec<PoolVersion>> {
+            self.inner.list_versions(id).await
+        }
+
+        async fn create_pool(&self, pool: &NewPool) -> anyhow::Result<Option<Pool>> {
+            self.inner.create_pool(pool).await
+        }
+
+        async fn update_pool(&self, id: &str, config: &PoolConfig) -> anyhow::Result<Option<Pool>> {
+            self.inner.update_pool(id, config).await
+        }
+
+        async fn transition(
+            &self,
+            id: &str,
+            from: &[PoolStatus],
+            to: PoolStatus,
+        ) -> anyhow::Result<Option<Pool>> {
+            self.inner.transition(id, from, to).await
+        }
+    }
+
+    /// Needs a Redis server: `REDIS_URL=redis://127.0.0.1:6379 cargo test -- --ignored`.
+    #[tokio::test]
+    #[ignore = "requires Redis"]
+    async fn list_read_before_a_pause_is_not_cached_after_it() {
+        let url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".into());
+        let client = redis::Client::open(url.as_str()).unwrap();
+        let mut redis = client.get_multiplexed_async_connection().await.unwrap();
+        let _: () = redis::cmd("DEL")
+            .arg(POOLS_KEY)
+            .arg(GENERATION_KEY)
+            .query_async(&mut redis)
+            .await
+            .unwrap();
+
+        let pool = test_pool(0.01);
+        let (read_tx, read_rx) = oneshot::channel();
+        let inner = Arc::new(SlowReads {
+            inner: InMemoryPoolConfigStore::new(),
+            read: tokio::sync::Mutex::new(Some(read_tx)),
+            release: Notify::new(),
+        });
+        inner
+            .create_pool(&NewPool {
+                id: pool.id.clone(),
+                config: pool.config.clone(),
+            })
+            .await
+            .unwrap();
+        let store = Arc::new(
+            RedisCachedPoolConfigStore::new(inner.clone(), &url, Duration::from_secs(30))
+                .await
+                .unwrap(),
+        );
+
+        // A reader misses the cache and loads the active pool...
+        let reader = tokio::spawn({
+            let store = store.clone();
+            async move { store.list_pools().await.unwrap() }
+        });
+        read_rx.await.unwrap();
+
+        // ...the pool is paused while its list is still on the way back...
+        store
+            .transition(&pool.id, &[PoolStatus::Active], PoolStatus::Paused)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // ...and the reader finishes with its stale list, which must not be cached.
+        inner.release.notify_one();
+        let stale = reader.await.unwrap();
+        assert_eq!(stale[0].status, PoolStatus::Active);
+
+        let pools = store.list_pools().await.unwrap();
+        assert_eq!(pools[0].status, PoolStatus::Paused);
+
+        let _: () = redis::cmd("DEL")
+            .arg(POOLS_KEY)
+            .arg(GENERATION_KEY)
+            .query_async(&mut redis)
+            .await
+            .unwrap();
+    }
+}
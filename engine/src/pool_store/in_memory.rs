@@ -0,0 +1,159 @@
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::{CompletedCycle, PoolPlay, PoolStore};
+
+/// Plays remembered for redelivered wagers; the oldest are forgotten first. Bounded by count
+/// rather than by time so long simulations don't keep every wager.
+const MAX_RECORDED_PLAYS: usize = 100_000;
+use crate::domain::models::{Pool, SiteContribution};
+
+struct PoolState {
+    value: u64,
+    cycle: u64,
+    seed: u64,
+    started_at: DateTime<Utc>,
+    contributions: u64,
+    wagers: u64,
+    /// Contributions and wager count per site for the current cycle.
+    sites: BTreeMap<i32, (u64, u64)>,
+}
+
+impl PoolState {
+    fn start(cycle: u64, seed: u64) -> Self {
+        Self {
+            value: seed,
+            cycle,
+            seed,
+            started_at: Utc::now(),
+            contributions: 0,
+            wagers: 0,
+            sites: BTreeMap::new(),
+        }
+    }
+}
+
+/// Keeps pool values in process memory, with the same cycle rules as the Redis store.
+/// Used by the simulator; state is lost on restart and not shared between replicas.
+#[derive(Default)]
+pub struct InMemoryPoolStore {
+    pools: Mutex<Pools>,
+}
+
+#[derive(Default)]
+struct Pools {
+    states: HashMap<String, PoolState>,
+    plays: HashMap<Uuid, PoolPlay>,
+    // Recorded wager ids, oldest first.
+    play_order: VecDeque<Uuid>,
+}
+
+impl InMemoryPoolStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current value of the pool, if it has been played.
+    pub fn value(&self, pool_id: &str) -> Option<u64> {
+        self.pools
+            .lock()
+            .expect("pool state poisoned")
+            .states
+            .get(pool_id)
+            .map(|state| state.value)
+    }
+}
+
+#[async_trait]
+impl PoolStore for InMemoryPoolStore {
+    async fn contribute(
+        &self,
+        pool: &Pool,
+        wager_id: Uuid,
+        site_id: i32,
+        contribution: u64,
+        roll: f64,
+    ) -> anyhow::Result<PoolPlay> {
+        let mut pools = self.pools.lock().expect("pool state poisoned");
+        if let Some(play) = pools.plays.get(&wager_id) {
+            return Ok(play.clone());
+        }
+        let state = pools
+            .states
+            .entry(pool.id.clone())
+            .or_insert_with(|| PoolState::start(1, pool.config.seed));
+
+        state.value += contribution;
+        state.contributions += contribution;
+        state.wagers += 1;
+        let site = state.sites.entry(site_id).or_default();
+        site.0 += contribution;
+        site.1 += 1;
+
+        let pool_value = state.value;
+        let cycle_id = state.cycle;
+        let won = roll < pool.config.hit_probability;
+        let completed_cycle = won.then(|| CompletedCycle {
+            started_at: state.started_at,
+            seed: state.seed,
+            total_contributions: state.contributions,
+            wager_count: state.wagers,
+            trigger_roll: roll,
+            site_contributions: state
+                .sites
+                .iter()
+                .map(
+                    |(&site_id, &(contributions, wager_count))| SiteContribution {
+                        site_id,
+                        contributions,
+                        wager_count,
+                    },
+                )
+                .collect(),
+        });
+        if won {
+            *state = PoolState::start(cycle_id + 1, pool.config.seed);
+        }
+
+        let play = PoolPlay {
+            pool_id: pool.id.clone(),
+            pool_version: pool.version,
+            won,
+            contribution,
+            pool_value,
+            cycle_id,
+            completed_cycle,
+        };
+        let full = pools.play_order.len() >= MAX_RECORDED_PLAYS;
+        if let Some(oldest) = full.then(|| pools.play_order.pop_front()).flatten() {
+            pools.plays.remove(&oldest);
+        }
+        pools.play_order.push_back(wager_id);
+        pools.plays.insert(wager_id, play.clone());
+        Ok(play)
+    }
+
+    async fn recorded_play(&self, wager_id: Uuid) -> anyhow::Result<Option<PoolPlay>> {
+        let pools = self.pools.lock().expect("pool state poisoned");
+        Ok(pools.plays.get(&wager_id).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::InMemoryPoolStore;
+    use crate::pool_store::tests::{hammer, test_pool};
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    async fn concurrent_wagers_have_one_winner_per_cycle() {
+        hammer(Arc::new(InMemoryPoolStore::new()), test_pool(0.01)).await;
+    }
+}
@@ -0,0 +1,180 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::models::{Pool, SiteContribution};
+
+pub mod in_memory;
+pub mod redis_store;
+
+/// How long a wager's play is remembered, so a redelivered wager does not contribute twice.
+pub const PLAY_RECORD_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Outcome of adding one wager's contribution to a pool.
+#[derive(Clone, Debug)]
+pub struct PoolPlay {
+    /// Pool and pool version the wager played on.
+    pub pool_id: String,
+    pub pool_version: i32,
+    pub won: bool,
+    /// What the wager added to the pool.
+    pub contribution: u64,
+    /// Pool value including this contribution; on a win, the amount paid out.
+    pub pool_value: u64,
+    /// Cycle the wager played in. Every cycle ends with exactly one winner.
+    pub cycle_id: u64,
+    /// Totals of the cycle this wager won; `None` unless `won`.
+    pub completed_cycle: Option<CompletedCycle>,
+}
+
+/// A pool cycle as it stood when its winning wager closed it.
+#[derive(Clone, Debug)]
+pub struct CompletedCycle {
+    pub started_at: DateTime<Utc>,
+    pub seed: u64,
+    /// Contributions made during the cycle, including the winning wager's.
+    pub total_contributions: u64,
+    pub wager_count: u64,
+    /// The RNG roll that triggered the win.
+    pub trigger_roll: f64,
+    /// Contributions per site, ordered by site id; they add up to `total_contributions`.
+    pub site_contributions: Vec<SiteContribution>,
+}
+
+#[async_trait]
+pub trait PoolStore: Send + Sync {
+    /// Adds `contribution` from `site_id` to the pool and, when `roll` is below the pool's
+    /// hit probability, pays the pool out and starts the next cycle from the seed, all as
+    /// one atomic step.
+    ///
+    /// The play is recorded under `wager_id` for [`PLAY_RECORD_TTL`]; contributing the same
+    /// wager again returns the recorded play instead, so a redelivered wager that already won
+    /// is still paid out and one that lost does not contribute twice.
+    async fn contribute(
+        &self,
+        pool: &Pool,
+        wager_id: Uuid,
+        site_id: i32,
+        contribution: u64,
+        roll: f64,
+    ) -> anyhow::Result<PoolPlay>;
+
+    /// The play recorded for `wager_id`, if it has played within [`PLAY_RECORD_TTL`].
+    async fn recorded_play(&self, wager_id: Uuid) -> anyhow::Result<Option<PoolPlay>>;
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{
+        collections::{BTreeMap, HashMap},
+        sync::Arc,
+    };
+
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::{PoolPlay, PoolStore};
+    use crate::domain::models::{Pool, PoolConfig, PoolStatus};
+
+    const WAGERS: usize = 5_000;
+    const CONTRIBUTION: u64 = 7;
+    const SEED: u64 = 1_000;
+    const SITES: i32 = 3;
+
+    pub(crate) fn test_pool(hit_probability: f64) -> Pool {
+        Pool {
+            id: format!("test-{}", Uuid::new_v4()),
+            status: PoolStatus::Active,
+            version: 1,
+            config: PoolConfig {
+                site_ids: (1..=SITES).collect(),
+                seed: SEED,
+                contribution_rate: 0.0,
+                site_rates: BTreeMap::new(),
+                hit_probability,
+            },
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Plays `WAGERS` wagers on one pool from many tasks at once, replaying every tenth as a
+    /// redelivery, and checks that every completed cycle had exactly one winner and that no
+    /// contribution was lost or counted twice.
+    pub(crate) async fn hammer(store: Arc<dyn PoolStore>, pool: Pool) {
+        let pool = Arc::new(pool);
+        let tasks: Vec<_> = (0..WAGERS)
+            .map(|i| {
+                let store = store.clone();
+                let pool = pool.clone();
+                tokio::spawn(async move {
+                    let wager_id = Uuid::new_v4();
+                    let site_id = i as i32 % SITES + 1;
+                    let play = store
+                        .contribute(&pool, wager_id, site_id, CONTRIBUTION, rand::random())
+                        .await
+                        .unwrap();
+                    if i % 10 == 0 {
+                        let replayed = store
+                            .contribute(&pool, wager_id, site_id, CONTRIBUTION, 0.0)
+                            .await
+                            .unwrap();
+                        assert_eq!(replayed.won, play.won);
+                        assert_eq!(replayed.cycle_id, play.cycle_id);
+                        assert_eq!(replayed.pool_value, play.pool_value);
+                    }
+                    play
+                })
+            })
+            .collect();
+        let mut plays: Vec<PoolPlay> = Vec::with_capacity(WAGERS);
+        for task in tasks {
+            plays.push(task.await.unwrap());
+        }
+
+        let mut wagers_by_cycle = HashMap::<u64, u64>::new();
+        let mut winners_by_cycle = HashMap::<u64, Vec<&PoolPlay>>::new();
+        for play in &plays {
+            *wagers_by_cycle.entry(play.cycle_id).or_default() += 1;
+            if play.won {
+                winners_by_cycle
+                    .entry(play.cycle_id)
+                    .or_default()
+                    .push(play);
+            }
+        }
+
+        let last_cycle = *wagers_by_cycle.keys().max().unwrap();
+        assert_eq!(
+            wagers_by_cycle.len() as u64,
+            last_cycle,
+            "cycles are contiguous"
+        );
+        let mut contributed = 0;
+        for cycle_id in 1..=last_cycle {
+            let wagers = wagers_by_cycle[&cycle_id];
+            match winners_by_cycle.get(&cycle_id).map(Vec::as_slice) {
+                Some([winner]) => {
+                    let cycle = winner.completed_cycle.as_ref().unwrap();
+                    assert_eq!(cycle.wager_count, wagers, "cycle {cycle_id} wager count");
+                    assert_eq!(cycle.total_contributions, wagers * CONTRIBUTION);
+                    assert_eq!(winner.pool_value, SEED + cycle.total_contributions);
+                    let by_site: u64 = cycle
+                        .site_contributions
+                        .iter()
+                        .map(|site| site.contributions)
+                        .sum();
+                    assert_eq!(by_site, cycle.total_contributions);
+                    contributed += cycle.total_contributions;
+                }
+                None => {
+                    assert_eq!(cycle_id, last_cycle, "only the open cycle has no winner");
+                    contributed += wagers * CONTRIBUTION;
+                }
+                Some(winners) => panic!("cycle {cycle_id} had {} winners", winners.len()),
+            }
+        }
+        assert_eq!(contributed, WAGERS as u64 * CONTRIBUTION);
+    }
+}
@@ -0,0 +1,274 @@
+use async_trait::async_trait;
+use chrono::DateTime;
+use redis::{Script, aio::ConnectionManager};
+
+use uuid::Uuid;
+
+use super::{CompletedCycle, PLAY_RECORD_TTL, PoolPlay, PoolStore};
+use crate::domain::models::{Pool, SiteContribution};
+
+// Returns the play recorded in the `play_key` list as a contribute reply, with the pool id
+// and version kept in the `pool_key` hash, or nil if the wager has not played. Plays recorded
+// before the pool was kept fall back to the given pool id and version.
+const REPLAY_FUNCTION: &str = r#"
+local function replay(play_key, pool_key, pool_id, pool_version)
+    local played = redis.call('LRANGE', play_key, 0, -1)
+    if #played == 0 then
+        return nil
+    end
+    local sites = {}
+    for i = 10, #played do
+        table.insert(sites, played[i])
+    end
+    local pool = redis.call('HMGET', pool_key, 'pool_id', 'version')
+    return {played[1], played[2], played[3], played[4], played[5], played[6], played[7],
+        sites, played[8], played[9], pool[1] or pool_id, pool[2] or pool_version}
+end
+"#;
+
+// Contribution, win check and reset run as one script, so however many engine replicas
+// play the same pool, each cycle has exactly one winner. Per-site counters live in the same
+// hash as `site:<id>:contributions` and `site:<id>:wagers`. Returns
+// {won, value, cycle, cycle_seed, cycle_started_at_ms, cycle_contributions, cycle_wagers,
+//  sites, roll, contribution, pool_id, pool_version}, where `sites` is a flat
+// {site_id, contributions, wagers, ...} list on a win.
+// The reply is also kept in the KEYS[2] list, and the pool it played on in the KEYS[3] hash,
+// and returned as is when the same wager plays again, so a redelivered wager neither
+// contributes twice nor loses its win.
+const CONTRIBUTE_SCRIPT: &str = r#"
+local seed = tonumber(ARGV[1])
+local contribution = tonumber(ARGV[2])
+local roll = tonumber(ARGV[3])
+local hit_probability = tonumber(ARGV[4])
+local site_id = ARGV[5]
+local play_ttl_ms = tonumber(ARGV[6])
+
+local played = replay(KEYS[2], KEYS[3], ARGV[7], ARGV[8])
+if played then
+    return played
+end
+
+local function record(reply)
+    redis.call('RPUSH', KEYS[2], reply[1], reply[2], reply[3], reply[4], reply[5], reply[6],
+        reply[7], ARGV[3], ARGV[2], unpack(reply[8]))
+    redis.call('PEXPIRE', KEYS[2], play_ttl_ms)
+    redis.call('HSET', KEYS[3], 'pool_id', ARGV[7], 'version', ARGV[8])
+    redis.call('PEXPIRE', KEYS[3], play_ttl_ms)
+    table.insert(reply, ARGV[3])
+    table.insert(reply, ARGV[2])
+    table.insert(reply, ARGV[7])
+    table.insert(reply, ARGV[8])
+    return reply
+end
+
+local function start_cycle(cycle)
+    local time = redis.call('TIME')
+    local now_ms = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+    redis.call('DEL', KEYS[1])
+    redis.call('HSET', KEYS[1], 'value', seed, 'cycle', cycle, 'seed', seed,
+        'started_at', now_ms, 'contributions', 0, 'wagers', 0)
+end
+
+if redis.call('EXISTS', KEYS[1]) == 0 then
+    start_cycle(1)
+end
+
+local value = redis.call('HINCRBY', KEYS[1], 'value', contribution)
+local contributions = redis.call('HINCRBY', KEYS[1], 'contributions', contribution)
+local wagers = redis.call('HINCRBY', KEYS[1], 'wagers', 1)
+redis.call('HINCRBY', KEYS[1], 'site:' .. site_id .. ':contributions', contribution)
+redis.call('HINCRBY', KEYS[1], 'site:' .. site_id .. ':wagers', 1)
+local state = redis.call('HMGET', KEYS[1], 'cycle', 'seed', 'started_at')
+local cycle = tonumber(state[1])
+local cycle_seed = tonumber(state[2]) or seed
+local started_at = tonumber(state[3]) or 0
+
+if roll < hit_probability then
+    local sites = {}
+    local fields = redis.call('HGETALL', KEYS[1])
+    for i = 1, #fields, 2 do
+        local site = string.match(fields[i], '^site:(.+):contributions$')
+        if site then
+            table.insert(sites, tonumber(site))
+            table.insert(sites, tonumber(fields[i + 1]))
+            table.insert(sites, tonumber(redis.call('HGET', KEYS[1], 'site:' .. site .. ':wagers')))
+        end
+    end
+    start_cycle(cycle + 1)
+    return record({1, value, cycle, cycle_seed, started_at, contributions, wagers, sites})
+end
+return record({0, value, cycle, cycle_seed, started_at, contributions, wagers, {}})
+"#;
+
+// Returns the recorded play of the wager with the KEYS[1] play list, or nil if it has not
+// played or was recorded without its pool.
+const RECORDED_PLAY_SCRIPT: &str = r#"
+local played = replay(KEYS[1], KEYS[2], false, false)
+if played and played[11] then
+    return played
+end
+return nil
+"#;
+
+/// A contribute reply, as documented on [`CONTRIBUTE_SCRIPT`].
+type PlayReply = (
+    i64,
+    u64,
+    u64,
+    u64,
+    i64,
+    u64,
+    u64,
+    Vec<i64>,
+    f64,
+    u64,
+    String,
+    i32,
+);
+
+/// Keeps pool values and cycles in Redis, shared by every engine replica.
+pub struct RedisPoolStore {
+    redis: ConnectionManager,
+    script: Script,
+    recorded_play_script: Script,
+}
+
+impl RedisPoolStore {
+    pub async fn new(redis_url: &str) -> anyhow::Result<Self> {
+        let client = redis::Client::open(redis_url)?;
+        let redis = ConnectionManager::new(client).await?;
+        Ok(Self {
+            redis,
+            script: Script::new(&format!("{REPLAY_FUNCTION}{CONTRIBUTE_SCRIPT}")),
+            recorded_play_script: Script::new(&format!("{REPLAY_FUNCTION}{RECORDED_PLAY_SCRIPT}")),
+        })
+    }
+
+    /// Redis hash holding the pool's `value`, current `cycle` and that cycle's totals.
+    pub fn key(pool_id: &str) -> String {
+        format!("jackpot:pool:{pool_id}")
+    }
+
+    /// Redis list holding the recorded play of a wager.
+    fn play_key(wager_id: Uuid) -> String {
+        format!("jackpot:play:{wager_id}")
+    }
+
+    /// Redis hash holding the pool id and version a wager played on.
+    fn play_pool_key(wager_id: Uuid) -> String {
+        format!("jackpot:play:{wager_id}:pool")
+    }
+}
+
+#[async_trait]
+impl PoolStore for RedisPoolStore {
+    async fn contribute(
+        &self,
+        pool: &Pool,
+        wager_id: Uuid,
+        site_id: i32,
+        contribution: u64,
+        roll: f64,
+    ) -> anyhow::Result<PoolPlay> {
+        let reply: PlayReply = self
+            .script
+            .key(Self::key(&pool.id))
+            .key(Self::play_key(wager_id))
+            .key(Self::play_pool_key(wager_id))
+            .arg(pool.config.seed)
+            .arg(contribution)
+            .arg(roll)
+            .arg(pool.config.hit_probability)
+            .arg(site_id)
+            .arg(PLAY_RECORD_TTL.as_millis() as u64)
+            .arg(&pool.id)
+            .arg(pool.version)
+            .invoke_async(&mut self.redis.clone())
+            .await?;
+
+        Ok(into_play(reply))
+    }
+
+    async fn recorded_play(&self, wager_id: Uuid) -> anyhow::Result<Option<PoolPlay>> {
+        let reply: Option<PlayReply> = self
+            .recorded_play_script
+            .key(Self::play_key(wager_id))
+            .key(Self::play_pool_key(wager_id))
+            .invoke_async(&mut self.redis.clone())
+            .await?;
+
+        Ok(reply.map(into_play))
+    }
+}
+
+fn into_play(reply: PlayReply) -> PoolPlay {
+    let (
+        won,
+        pool_value,
+        cycle_id,
+        seed,
+        started_at_ms,
+        total_contributions,
+        wager_count,
+        sites,
+        trigger_roll,
+        contribution,
+        pool_id,
+        pool_version,
+    ) = reply;
+
+    let won = won == 1;
+    let mut site_contributions: Vec<SiteContribution> = sites
+        .chunks_exact(3)
+        .map(|site| SiteContribution {
+            site_id: site[0] as i32,
+            contributions: site[1] as u64,
+            wager_count: site[2] as u64,
+        })
+        .collect();
+    site_contributions.sort_by_key(|site| site.site_id);
+    let completed_cycle = won.then(|| CompletedCycle {
+        started_at: DateTime::from_timestamp_millis(started_at_ms).unwrap_or_default(),
+        seed,
+        total_contributions,
+        wager_count,
+        trigger_roll,
+        site_contributions,
+    });
+
+    PoolPlay {
+        pool_id,
+        pool_version,
+        won,
+        contribution,
+        pool_value,
+        cycle_id,
+        completed_cycle,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::RedisPoolStore;
+    use crate::pool_store::tests::{hammer, test_pool};
+
+    /// Needs a Redis server: `REDIS_URL=redis://127.0.0.1:6379 cargo test -- --ignored`.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    #[ignore = "requires Redis"]
+    async fn concurrent_wagers_have_one_winner_per_cycle() {
+        let url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".into());
+        let pool = test_pool(0.01);
+        let key = RedisPoolStore::key(&pool.id);
+        hammer(Arc::new(RedisPoolStore::new(&url).await.unwrap()), pool).await;
+
+        let client = redis::Client::open(url).unwrap();
+        let mut redis = client.get_multiplexed_async_connection().await.unwrap();
+        let _: () = redis::cmd("DEL")
+            .arg(key)
+            .query_async(&mut redis)
+            .await
+            .unwrap();
+    }
+}
@@ -1,6 +1,11 @@
 use crate::configuration::ApplicationSettings;
+use crate::handlers::pools;
 use crate::messaging::connection::RabbitConnection;
+use crate::metrics;
+use crate::services::pool_admin::PoolAdminService;
 use anyhow::Result;
+use secrecy::{ExposeSecret, SecretString};
+use std::convert::Infallible;
 use std::future::Future;
 use std::sync::Arc;
 use tracing::{debug, info, warn};
@@ -11,6 +16,7 @@ pub async fn start_server(
     app_config: ApplicationSettings,
     gateway_connection: Arc<RabbitConnection>,
     storage_connection: Arc<RabbitConnection>,
+    pool_admin: Arc<PoolAdminService>,
 ) -> Result<impl Future<Output = ()>> {
     info!("Starting server on {}:{}", app_config.host, app_config.port);
     let health_route = warp::path("health").and_then(move || {
@@ -20,7 +26,136 @@ pub async fn start_server(
         health_check(connections)
     });
 
-    Ok(warp::serve(health_route).run((app_config.host, app_config.port)))
+    let metrics_route = warp::path("metrics")
+        .and(warp::get())
+        .and_then(metrics_handler);
+
+    // Pool administration is an internal API: every route requires the admin token, and the
+    // port should only be reachable on the service network.
+    let admin_auth = with_admin_token(app_config.admin_token.clone().map(Arc::new));
+    if app_config.admin_token.is_none() {
+        warn!("No admin token is configured; the admin API refuses every request");
+    }
+
+    let pool_list_route = warp::path!("admin" / "pools")
+        .and(admin_auth.clone())
+        .and(warp::get())
+        .and(with_pool_admin(pool_admin.clone()))
+        .and_then(pools::list_pools);
+
+    let pool_create_route = warp::path!("admin" / "pools")
+        .and(admin_auth.clone())
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_pool_admin(pool_admin.clone()))
+        .and_then(pools::create_pool);
+
+    let pool_route = warp::path!("admin" / "pools" / String)
+        .and(admin_auth.clone())
+        .and(warp::get())
+        .and(with_pool_admin(pool_admin.clone()))
+        .and_then(pools::get_pool);
+
+    let pool_update_route = warp::path!("admin" / "pools" / String)
+        .and(admin_auth.clone())
+        .and(warp::put())
+        .and(warp::body::json())
+        .and(with_pool_admin(pool_admin.clone()))
+        .and_then(pools::update_pool);
+
+    let pool_versions_route = warp::path!("admin" / "pools" / String / "versions")
+        .and(admin_auth.clone())
+        .and(warp::get())
+        .and(with_pool_admin(pool_admin.clone()))
+        .and_then(pools::list_versions);
+
+    let pool_pause_route = warp::path!("admin" / "pools" / String / "pause")
+        .and(admin_auth.clone())
+        .and(warp::post())
+        .and(with_pool_admin(pool_admin.clone()))
+        .and_then(pools::pause_pool);
+
+    let pool_resume_route = warp::path!("admin" / "pools" / String / "resume")
+        .and(admin_auth.clone())
+        .and(warp::post())
+        .and(with_pool_admin(pool_admin.clone()))
+        .and_then(pools::resume_pool);
+
+    let pool_close_route = warp::path!("admin" / "pools" / String / "close")
+        .and(admin_auth)
+        .and(warp::post())
+        .and(with_pool_admin(pool_admin))
+        .and_then(pools::close_pool);
+
+    let routes = health_route
+        .or(metrics_route)
+        .or(pool_list_route)
+        .or(pool_create_route)
+        .or(pool_route)
+        .or(pool_update_route)
+        .or(pool_versions_route)
+        .or(pool_pause_route)
+        .or(pool_resume_route)
+        .or(pool_close_route)
+        .recover(unauthorized);
+
+    Ok(warp::serve(routes).run((app_config.host, app_config.port)))
+}
+
+fn with_pool_admin(
+    pool_admin: Arc<PoolAdminService>,
+) -> impl Filter<Extract = (Arc<PoolAdminService>,), Error = Infallible> + Clone {
+    warp::any().map(move || pool_admin.clone())
+}
+
+#[derive(Debug)]
+struct Unauthorized;
+
+impl warp::reject::Reject for Unauthorized {}
+
+/// Passes requests carrying `Authorization: Bearer <admin token>`; rejects everything when
+/// no token is configured.
+fn with_admin_token(
+    token: Option<Arc<SecretString>>,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::header::optional::<String>("authorization")
+        .and_then(move |header: Option<String>| {
+            let token = token.clone();
+            async move {
+                let presented = header
+                    .as_deref()
+                    .and_then(|value| value.strip_prefix("Bearer "));
+                match (token, presented) {
+                    (Some(token), Some(presented))
+                        if constant_time_eq(
+                            token.expose_secret().as_bytes(),
+                            presented.as_bytes(),
+                        ) =>
+                    {
+                        Ok(())
+                    }
+                    _ => Err(warp::reject::custom(Unauthorized)),
+                }
+            }
+        })
+        .untuple_one()
+}
+
+/// Compares without short-circuiting, so response time doesn't reveal how much of the token
+/// was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+async fn unauthorized(rejection: Rejection) -> Result<impl Reply, Rejection> {
+    if rejection.find::<Unauthorized>().is_some() {
+        Ok(warp::reply::with_status(
+            warp::reply::json(&"Unauthorized"),
+            StatusCode::UNAUTHORIZED,
+        ))
+    } else {
+        Err(rejection)
+    }
 }
 
 async fn health_check(
@@ -55,3 +190,23 @@ async fn health_check(
         ))
     }
 }
+
+async fn metrics_handler() -> Result<impl Reply, Rejection> {
+    match metrics::render() {
+        Ok(body) => Ok(warp::reply::with_status(
+            warp::reply::with_header(body, "content-type", "text/plain; version=0.0.4"),
+            StatusCode::OK,
+        )),
+        Err(e) => {
+            warn!("Failed to render metrics: {:?}", e);
+            Ok(warp::reply::with_status(
+                warp::reply::with_header(
+                    "Failed to render metrics".to_string(),
+                    "content-type",
+                    "text/plain",
+                ),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    }
+}
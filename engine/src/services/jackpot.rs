@@ -1,23 +1,218 @@
-use crate::domain::models::WagerRequest;
-use redis::aio::ConnectionManager;
+use std::sync::Arc;
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{Pool, PoolStatus, WagerRequest},
+    pool_config::PoolConfigStore,
+    pool_store::{PoolPlay, PoolStore},
+};
+
+use super::rolls::RollSource;
+
+/// Why a wager cannot be played. Everything but `Other` is a final answer for the wager.
+#[derive(Debug, Error)]
+pub enum JackpotError {
+    #[error("No jackpot pool is configured for site {0}")]
+    NoPool(i32),
+    #[error("Pool {0} is paused")]
+    PoolPaused(String),
+    #[error("Pool {0} is closed to new wagers")]
+    PoolClosed(String),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl JackpotError {
+    /// Code returned to the gateway for rejected wagers; `None` for internal failures.
+    pub fn code(&self) -> Option<&'static str> {
+        match self {
+            JackpotError::NoPool(_) => Some("no_pool"),
+            JackpotError::PoolPaused(_) => Some("pool_paused"),
+            JackpotError::PoolClosed(_) => Some("pool_closed"),
+            JackpotError::Other(_) => None,
+        }
+    }
+}
 
 pub struct JackpotService {
-    redis: ConnectionManager,
+    pool_store: Arc<dyn PoolStore>,
+    pool_config: Arc<dyn PoolConfigStore>,
+    rolls: Arc<dyn RollSource>,
 }
 
 impl JackpotService {
-    pub async fn new(redis_url: &str) -> anyhow::Result<Self> {
-        let client = redis::Client::open(redis_url)?;
-        let redis = ConnectionManager::new(client).await?;
-        Ok(Self { redis })
+    pub fn new(
+        pool_store: Arc<dyn PoolStore>,
+        pool_config: Arc<dyn PoolConfigStore>,
+        rolls: Arc<dyn RollSource>,
+    ) -> Self {
+        Self {
+            pool_store,
+            pool_config,
+            rolls,
+        }
+    }
+
+    /// The pool that wagers from `site_id` contribute to, at its current version. Only
+    /// active pools admit new wagers.
+    pub async fn pool_for_site(&self, site_id: i32) -> Result<Pool, JackpotError> {
+        let pool = self
+            .pool_config
+            .list_pools()
+            .await?
+            .into_iter()
+            .find(|pool| {
+                pool.status != PoolStatus::Closed && pool.config.site_ids.contains(&site_id)
+            })
+            .ok_or(JackpotError::NoPool(site_id))?;
+
+        match pool.status {
+            PoolStatus::Active => Ok(pool),
+            PoolStatus::Paused => Err(JackpotError::PoolPaused(pool.id)),
+            PoolStatus::Closing | PoolStatus::Closed => Err(JackpotError::PoolClosed(pool.id)),
+        }
+    }
+
+    /// Re-checks an admitted wager's pool right before it contributes, so a pause takes
+    /// effect for wagers already waiting on the pool. Closing pools let them finish.
+    pub async fn ensure_open(&self, pool: &Pool) -> Result<(), JackpotError> {
+        let status = self
+            .pool_config
+            .list_pools()
+            .await?
+            .into_iter()
+            .find(|current| current.id == pool.id)
+            .map_or(PoolStatus::Closed, |current| current.status);
+
+        match status {
+            PoolStatus::Active | PoolStatus::Closing => Ok(()),
+            PoolStatus::Paused => Err(JackpotError::PoolPaused(pool.id.clone())),
+            PoolStatus::Closed => Err(JackpotError::PoolClosed(pool.id.clone())),
+        }
+    }
+
+    /// Plays the wager on its site's pool; returns the play and the pool it was admitted to.
+    ///
+    /// A wager that has played already, e.g. a redelivery after a later step failed, gets
+    /// its recorded play back and `None` for the pool, before the pool is looked up or
+    /// checked: a winner has already reset the pool, so its win must still be stored, paid
+    /// and announced if the pool was paused, closed or remapped in the meantime.
+    pub async fn play_wager(
+        &self,
+        wager_id: Uuid,
+        request: &WagerRequest,
+    ) -> Result<(PoolPlay, Option<Pool>), JackpotError> {
+        if let Some(play) = self.pool_store.recorded_play(wager_id).await? {
+            return Ok((play, None));
+        }
+
+        let pool = self.pool_for_site(request.site_id).await?;
+        self.ensure_open(&pool).await?;
+        let play = self.play(&pool, wager_id, request).await?;
+        Ok((play, Some(pool)))
     }
 
-    pub async fn update_balance_and_check_win(
+    /// Adds the wager's contribution to `pool`, at its site's rate, and checks it for a win.
+    /// Playing the same `wager_id` again returns its first play.
+    pub async fn play(
         &self,
+        pool: &Pool,
+        wager_id: Uuid,
         request: &WagerRequest,
-    ) -> anyhow::Result<(bool, u64)> {
-        let won = rand::random::<bool>(); // TODO Replace with certified RNG logic
-        let new_balance = if won { 0 } else { request.amount };
-        Ok((won, new_balance))
+    ) -> anyhow::Result<PoolPlay> {
+        let rate = pool.config.rate_for_site(request.site_id);
+        let contribution = (request.amount as f64 * rate).floor() as u64;
+        let roll = self.rolls.roll();
+        self.pool_store
+            .contribute(pool, wager_id, request.site_id, contribution, roll)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use uuid::Uuid;
+
+    use super::{JackpotError, JackpotService};
+    use crate::{
+        domain::models::{NewPool, PoolStatus, WagerRequest},
+        pool_config::{PoolConfigStore, in_memory::InMemoryPoolConfigStore},
+        pool_store::{in_memory::InMemoryPoolStore, tests::test_pool},
+        services::rolls::RollSource,
+    };
+
+    /// Rolls below every hit probability, so each fresh wager wins.
+    struct WinningRolls;
+
+    impl RollSource for WinningRolls {
+        fn roll(&self) -> f64 {
+            0.0
+        }
+    }
+
+    fn wager(wager_id: Uuid, site_id: i32) -> WagerRequest {
+        WagerRequest {
+            id: Some(wager_id),
+            amount: 1_000,
+            site_id,
+            user_id: 1,
+            game_id: 1,
+            currency: None,
+            received_at: None,
+            cheat_code: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn redelivered_win_is_replayed_after_its_pool_is_paused() {
+        let pool_config = Arc::new(InMemoryPoolConfigStore::new());
+        let mut pool = test_pool(1.0);
+        pool.config.contribution_rate = 0.01;
+        pool_config
+            .create_pool(&NewPool {
+                id: pool.id.clone(),
+                config: pool.config.clone(),
+            })
+            .await
+            .unwrap();
+        let service = JackpotService::new(
+            Arc::new(InMemoryPoolStore::new()),
+            pool_config.clone(),
+            Arc::new(WinningRolls),
+        );
+
+        let wager_id = Uuid::new_v4();
+        let (first, admitted) = service
+            .play_wager(wager_id, &wager(wager_id, 1))
+            .await
+            .unwrap();
+        assert!(first.won);
+        assert_eq!(admitted.unwrap().id, pool.id);
+
+        // The first attempt failed after the win, and the pool is paused before the redelivery.
+        pool_config
+            .transition(&pool.id, &[PoolStatus::Active], PoolStatus::Paused)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let (replayed, admitted) = service
+            .play_wager(wager_id, &wager(wager_id, 1))
+            .await
+            .unwrap();
+        assert!(admitted.is_none());
+        assert!(replayed.won);
+        assert_eq!(replayed.pool_id, pool.id);
+        assert_eq!(replayed.cycle_id, first.cycle_id);
+        assert_eq!(replayed.pool_value, first.pool_value);
+        assert_eq!(replayed.contribution, first.contribution);
+
+        let new_wager = Uuid::new_v4();
+        let refused = service.play_wager(new_wager, &wager(new_wager, 1)).await;
+        assert!(matches!(refused, Err(JackpotError::PoolPaused(_))));
     }
 }
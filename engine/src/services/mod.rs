@@ -1,2 +1,4 @@
 pub mod jackpot;
+pub mod pool_admin;
 pub mod processor;
+pub mod rolls;
@@ -0,0 +1,210 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use thiserror::Error;
+use tracing::{error, info};
+
+use crate::{
+    domain::models::{NewPool, Pool, PoolConfig, PoolStatus, PoolVersion},
+    pool_config::PoolConfigStore,
+};
+
+#[derive(Debug, Error)]
+pub enum PoolAdminError {
+    #[error("{0}")]
+    Invalid(String),
+    #[error("Pool {0} not found")]
+    NotFound(String),
+    #[error("Pool {0} already exists")]
+    AlreadyExists(String),
+    #[error("Pool {pool_id} is {}", status.as_str())]
+    InvalidState { pool_id: String, status: PoolStatus },
+    #[error("Sites {site_ids:?} already belong to pool {pool_id}")]
+    SiteConflict { pool_id: String, site_ids: Vec<i32> },
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// Creates and changes pools on behalf of the admin API. Each site may belong to at most
+/// one pool that is not closed, so every wager resolves to a single pool.
+///
+/// Pools move between `active` and `paused` freely; closing one is one-way: it stops
+/// taking new wagers and is closed for good once its in-flight wagers have drained.
+pub struct PoolAdminService {
+    pool_config: Arc<dyn PoolConfigStore>,
+}
+
+impl PoolAdminService {
+    pub fn new(pool_config: Arc<dyn PoolConfigStore>) -> Self {
+        Self { pool_config }
+    }
+
+    pub async fn list_pools(&self) -> Result<Vec<Pool>, PoolAdminError> {
+        Ok(self.pool_config.list_pools().await?)
+    }
+
+    pub async fn get_pool(&self, id: &str) -> Result<Pool, PoolAdminError> {
+        self.pool_config
+            .find_pool(id)
+            .await?
+            .ok_or_else(|| PoolAdminError::NotFound(id.to_string()))
+    }
+
+    pub async fn list_versions(&self, id: &str) -> Result<Vec<PoolVersion>, PoolAdminError> {
+        let versions = self.pool_config.list_versions(id).await?;
+        if versions.is_empty() {
+            return Err(PoolAdminError::NotFound(id.to_string()));
+        }
+        Ok(versions)
+    }
+
+    pub async fn create_pool(&self, pool: NewPool) -> Result<Pool, PoolAdminError> {
+        validate_pool_id(&pool.id)?;
+        pool.config.validate().map_err(PoolAdminError::Invalid)?;
+        self.check_sites(&pool.id, &pool.config).await?;
+
+        let created = self
+            .pool_config
+            .create_pool(&pool)
+            .await?
+            .ok_or_else(|| PoolAdminError::AlreadyExists(pool.id.clone()))?;
+        info!(pool_id = %created.id, "Created pool");
+        Ok(created)
+    }
+
+    pub async fn update_pool(&self, id: &str, config: PoolConfig) -> Result<Pool, PoolAdminError> {
+        config.validate().map_err(PoolAdminError::Invalid)?;
+        self.check_sites(id, &config).await?;
+
+        let Some(updated) = self.pool_config.update_pool(id, &config).await? else {
+            return Err(self.missing_or_invalid(id).await);
+        };
+        info!(pool_id = %updated.id, version = updated.version, "Updated pool configuration");
+        Ok(updated)
+    }
+
+    /// Freezes the pool: its wagers are rejected until it is resumed.
+    pub async fn pause(&self, id: &str) -> Result<Pool, PoolAdminError> {
+        self.transition(
+            id,
+            &[PoolStatus::Active, PoolStatus::Paused],
+            PoolStatus::Paused,
+        )
+        .await
+    }
+
+    pub async fn resume(&self, id: &str) -> Result<Pool, PoolAdminError> {
+        self.transition(
+            id,
+            &[PoolStatus::Paused, PoolStatus::Active],
+            PoolStatus::Active,
+        )
+        .await
+    }
+
+    /// Stops the pool taking new wagers; [`Self::finish_closing`] closes it once drained.
+    pub async fn close(&self, id: &str) -> Result<Pool, PoolAdminError> {
+        self.transition(
+            id,
+            &[PoolStatus::Active, PoolStatus::Paused, PoolStatus::Closing],
+            PoolStatus::Closing,
+        )
+        .await
+    }
+
+    /// Closes every pool that has been closing for at least `drain`, long enough for
+    /// wagers admitted before it started closing to have finished on every replica.
+    pub async fn finish_closing(&self, drain: Duration) -> anyhow::Result<()> {
+        let drain = chrono::Duration::from_std(drain)?;
+        let pools = self.pool_config.list_pools().await?;
+        for pool in pools.iter().filter(|pool| {
+            pool.status == PoolStatus::Closing && Utc::now() - pool.updated_at >= drain
+        }) {
+            if self
+                .pool_config
+                .transition(&pool.id, &[PoolStatus::Closing], PoolStatus::Closed)
+                .await?
+                .is_some()
+            {
+                info!(pool_id = %pool.id, "Closed drained pool");
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs [`Self::finish_closing`] every `interval` for the life of the process.
+    pub fn spawn_close_sweeper(self: Arc<Self>, interval: Duration, drain: Duration) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.finish_closing(drain).await {
+                    error!("Failed to close drained pools: {:?}", e);
+                }
+            }
+        });
+    }
+
+    async fn transition(
+        &self,
+        id: &str,
+        from: &[PoolStatus],
+        to: PoolStatus,
+    ) -> Result<Pool, PoolAdminError> {
+        let Some(updated) = self.pool_config.transition(id, from, to).await? else {
+            return Err(self.missing_or_invalid(id).await);
+        };
+        info!(pool_id = %updated.id, status = to.as_str(), "Changed pool status");
+        Ok(updated)
+    }
+
+    /// Rejects `config` if any of its sites already belong to another pool that is not closed.
+    async fn check_sites(&self, id: &str, config: &PoolConfig) -> Result<(), PoolAdminError> {
+        let pools = self.pool_config.list_pools().await?;
+        for other in pools
+            .iter()
+            .filter(|other| other.id != id && other.status != PoolStatus::Closed)
+        {
+            let site_ids: Vec<i32> = config
+                .site_ids
+                .iter()
+                .copied()
+                .filter(|site_id| other.config.site_ids.contains(site_id))
+                .collect();
+            if !site_ids.is_empty() {
+                return Err(PoolAdminError::SiteConflict {
+                    pool_id: other.id.clone(),
+                    site_ids,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    async fn missing_or_invalid(&self, id: &str) -> PoolAdminError {
+        match self.pool_config.find_pool(id).await {
+            Ok(Some(pool)) => PoolAdminError::InvalidState {
+                pool_id: pool.id,
+                status: pool.status,
+            },
+            Ok(None) => PoolAdminError::NotFound(id.to_string()),
+            Err(e) => PoolAdminError::Other(e),
+        }
+    }
+}
+
+/// Pool ids end up in URLs and Redis keys, so keep them to a safe alphabet.
+fn validate_pool_id(id: &str) -> Result<(), PoolAdminError> {
+    let valid = !id.is_empty()
+        && id.len() <= 64
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(PoolAdminError::Invalid(
+            "id must be 1-64 letters, digits, '-' or '_'".into(),
+        ))
+    }
+}
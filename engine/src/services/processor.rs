@@ -1,10 +1,14 @@
+use chrono::Utc;
 use std::sync::Arc;
 use tracing::instrument;
 use uuid::Uuid;
 
 use crate::{
-    domain::models::{ReceiptResponse, WagerRequest, WagerResponse},
+    domain::models::{
+        JackpotWonEvent, ProcessedWager, ReceiptResponse, WagerOutcome, WagerRequest, WagerResponse,
+    },
     messaging::{publish_client::PublishClient, rpc_client::RpcClient},
+    metrics::POOL_VALUE,
 };
 
 use super::jackpot::JackpotService;
@@ -13,47 +17,115 @@ pub struct JackpotProcessor {
     pub jackpot_service: Arc<JackpotService>,
     pub storage_rpc_client: Arc<RpcClient<ReceiptResponse>>,
     pub publish_client: Arc<PublishClient>,
+    pub events_client: Arc<PublishClient>,
 }
 
 impl JackpotProcessor {
     #[instrument(name = "process_wager", skip(self, request), fields(user_id = %request.user_id, amount = request.amount))]
     pub async fn process_wager(&self, request: WagerRequest) -> anyhow::Result<WagerResponse> {
         tracing::info!("Starting wager processing");
+        let received_at = request.received_at.unwrap_or_else(Utc::now);
 
-        let (won, new_balance) = self
-            .jackpot_service
-            .update_balance_and_check_win(&request)
-            .await?;
+        // Redeliveries of the wager replay its recorded play, so they are paid, stored and
+        // announced like the first delivery without contributing again.
+        let wager_id = request.id.unwrap_or_else(Uuid::new_v4);
+        let (play, pool) = self.jackpot_service.play_wager(wager_id, &request).await?;
+        let won = play.won;
 
         tracing::info!(
             won = won,
-            new_balance = new_balance,
+            pool_id = %play.pool_id,
+            pool_version = play.pool_version,
+            cycle_id = play.cycle_id,
+            pool_value = play.pool_value,
+            replayed = pool.is_none(),
             "Jackpot result determined"
         );
+        if let Some(pool) = &pool {
+            let current_value = if won {
+                pool.config.seed
+            } else {
+                play.pool_value
+            };
+            POOL_VALUE
+                .with_label_values(&[pool.id.as_str()])
+                .set(current_value as i64);
+        }
 
+        let wager = ProcessedWager {
+            id: wager_id,
+            site_id: request.site_id,
+            user_id: request.user_id,
+            game_id: request.game_id,
+            amount: request.amount,
+            pool_id: play.pool_id.clone(),
+            pool_version: play.pool_version,
+            cycle_id: play.cycle_id,
+            contribution: play.contribution,
+            win_amount: if won { play.pool_value } else { 0 },
+            outcome: if won {
+                WagerOutcome::Won
+            } else {
+                WagerOutcome::Lost
+            },
+            currency: request.currency.clone(),
+            received_at,
+            processed_at: Utc::now(),
+        };
         let mut response = WagerResponse {
-            wager_id: Uuid::new_v4(),
+            wager_id: wager.id,
             status: won.to_string(),
             amount: request.amount,
             receipt_id: None,
+            error: None,
         };
 
-        if won {
+        if let Some(cycle) = play.completed_cycle {
             tracing::info!("Jackpot won, sending RPC to storage with priority");
             let receipt_response = self
                 .storage_rpc_client
-                .call(&serde_json::to_string(&request)?, Some(10))
+                .call(&serde_json::to_string(&wager)?, Some(10))
                 .await?;
 
+            anyhow::ensure!(
+                receipt_response.status != "conflict",
+                "Storage holds a different wager with id {}",
+                wager_id
+            );
             tracing::info!(
                 receipt_id = receipt_response.receipt_id,
+                status = receipt_response.status,
                 "Received receipt ID from storage"
             );
-            response.receipt_id = Some(receipt_response.receipt_id);
+            response.receipt_id = receipt_response.receipt_id;
+
+            let event = JackpotWonEvent {
+                // Derived from the wager, so consumers can drop the copy a redelivery publishes.
+                event_id: Uuid::new_v5(&wager_id, b"jackpot.won"),
+                wager_id: response.wager_id,
+                site_id: request.site_id,
+                user_id: request.user_id,
+                game_id: request.game_id,
+                wager_amount: request.amount,
+                pool_id: play.pool_id.clone(),
+                cycle_id: play.cycle_id,
+                cycle_started_at: cycle.started_at,
+                seed: cycle.seed,
+                total_contributions: cycle.total_contributions,
+                wager_count: cycle.wager_count,
+                win_amount: play.pool_value,
+                trigger_roll: cycle.trigger_roll,
+                won_at: Utc::now(),
+                site_contributions: cycle.site_contributions,
+            };
+            self.events_client
+                .publish(&serde_json::to_string(&event)?, None)
+                .await?;
+            tracing::info!(event_id = %event.event_id, "Published jackpot win event");
         } else {
             tracing::info!("Jackpot lost, publishing to storage without priority");
             self.publish_client
-                .publish(&serde_json::to_string(&request)?, Some(1))
+                .publish(&serde_json::to_string(&wager)?, Some(1))
                 .await?;
             tracing::info!("Published loss transaction to storage");
         }
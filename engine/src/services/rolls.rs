@@ -0,0 +1,39 @@
+use std::sync::Mutex;
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+/// Source of the uniform `[0, 1)` rolls that decide whether a wager wins.
+pub trait RollSource: Send + Sync {
+    fn roll(&self) -> f64;
+}
+
+/// Rolls from the thread-local, OS-seeded generator.
+pub struct ThreadRolls;
+
+impl RollSource for ThreadRolls {
+    fn roll(&self) -> f64 {
+        rand::random::<f64>() // TODO Replace with certified RNG logic
+    }
+}
+
+/// Reproducible rolls, for simulations.
+pub struct SeededRolls {
+    rng: Mutex<StdRng>,
+}
+
+impl SeededRolls {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+}
+
+impl RollSource for SeededRolls {
+    fn roll(&self) -> f64 {
+        self.rng
+            .lock()
+            .expect("roll generator poisoned")
+            .random::<f64>()
+    }
+}
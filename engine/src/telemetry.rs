@@ -1,3 +1,10 @@
+use opentelemetry::global;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use std::sync::OnceLock;
 use tracing::Subscriber;
 use tracing::subscriber::set_global_default;
 use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
@@ -5,25 +12,66 @@ use tracing_log::LogTracer;
 use tracing_subscriber::fmt::MakeWriter;
 use tracing_subscriber::{EnvFilter, Registry, layer::SubscriberExt};
 
+static TRACER_PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+
+/// Builds the subscriber. Spans always carry OpenTelemetry trace ids so they can be
+/// propagated across RabbitMQ; they are exported only when `otlp_endpoint` is set.
 pub fn get_subscriber<Sink>(
     name: String,
     env_filter: String,
     sink: Sink,
+    otlp_endpoint: Option<&str>,
 ) -> impl Subscriber + Sync + Send
 where
     Sink: for<'a> MakeWriter<'a> + Send + Sync + 'static,
 {
     let env_filter =
         EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(env_filter));
+    let tracer_provider = build_tracer_provider(&name, otlp_endpoint);
+    let tracer = tracer_provider.tracer(name.clone());
     let formatting_layer = BunyanFormattingLayer::new(name, sink);
 
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    global::set_tracer_provider(tracer_provider.clone());
+    let _ = TRACER_PROVIDER.set(tracer_provider);
+
     Registry::default()
         .with(env_filter)
         .with(JsonStorageLayer)
         .with(formatting_layer)
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
 }
 
 pub fn init_subscriber(subscriber: impl Subscriber + Sync + Send) {
     LogTracer::init().expect("Failed to set logger");
     set_global_default(subscriber).expect("Failed to set subscriber");
 }
+
+/// Flushes spans that are still buffered for export. Call once before the process exits.
+pub fn shutdown_tracer_provider() {
+    let Some(tracer_provider) = TRACER_PROVIDER.get() else {
+        return;
+    };
+    if let Err(e) = tracer_provider.shutdown() {
+        tracing::warn!("Failed to shut down tracer provider: {:?}", e);
+    }
+}
+
+fn build_tracer_provider(service_name: &str, otlp_endpoint: Option<&str>) -> SdkTracerProvider {
+    let resource = Resource::builder()
+        .with_service_name(service_name.to_string())
+        .build();
+    let builder = SdkTracerProvider::builder().with_resource(resource);
+
+    match otlp_endpoint {
+        Some(endpoint) => {
+            let exporter = SpanExporter::builder()
+                .with_http()
+                .with_endpoint(endpoint)
+                .build()
+                .expect("Failed to build OTLP span exporter");
+            builder.with_batch_exporter(exporter).build()
+        }
+        None => builder.build(),
+    }
+}
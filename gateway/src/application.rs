@@ -1,19 +1,53 @@
-use std::net::TcpListener;
+use std::{net::TcpListener, sync::Arc, time::Duration};
 
 use crate::{
-    configuration::{Config, RabbitMqConfig},
+    auth::{
+        ApiKeyStore,
+        config_store::ConfigApiKeyStore,
+        nonces::{NonceStore, in_memory::InMemoryNonceStore, redis_store::RedisNonceStore},
+        postgres_store::PostgresApiKeyStore,
+        signature::SignatureVerifier,
+    },
+    clients::{callback_client::CallbackClient, storage_client::StorageClient},
+    configuration::{
+        ApiKeySource, AsyncWagersConfig, AuthConfig, BatchConfig, Config, MaintenanceConfig,
+        PostgresConfig, RateLimitConfig, RedisConfig, SigningConfig, StoreBackend,
+    },
     domain::models::WagerResponse,
+    maintenance::{
+        Maintenance, MaintenanceStore, in_memory::InMemoryMaintenanceStore,
+        redis_store::RedisMaintenanceStore,
+    },
     messaging::{connection::RabbitConnection, rpc_client::RpcClient},
+    rate_limit::{
+        BucketStore, RateLimiter, in_memory::InMemoryBucketStore, redis_store::RedisBucketStore,
+    },
     routes,
+    wager_status::{
+        WagerStatusStore, in_memory::InMemoryWagerStatusStore, redis_store::RedisWagerStatusStore,
+    },
+};
+use actix_web::{
+    App, HttpServer,
+    web::{Data, JsonConfig, PayloadConfig},
 };
-use actix_web::{App, HttpServer, web::Data};
 
 use actix_web::dev::Server;
+use anyhow::Context;
 use lapin::ExchangeKind;
+use secrecy::ExposeSecret;
+use tokio::signal::unix::{SignalKind, signal};
+
+/// Time left after failing pending RPCs for their 503 responses to be written.
+const RPC_SHUTDOWN_GRACE_SECS: u64 = 5;
 
 pub struct Application {
     port: u16,
     server: Server,
+    admin_server: Server,
+    connection: RabbitConnection,
+    rpc_client: Data<RpcClient<WagerResponse>>,
+    shutdown_timeout: Duration,
 }
 
 impl Application {
@@ -24,50 +58,273 @@ impl Application {
         );
         let listener = TcpListener::bind(address)?;
         let port = listener.local_addr().unwrap().port();
-        let server = run(
+        let admin_listener = TcpListener::bind(format!(
+            "{}:{}",
+            configuration.application.host, configuration.application.admin_port
+        ))?;
+        let services = AppServices::build(&configuration).await?;
+        let connection = RabbitConnection::new(&configuration.rabbitmq.uri).await?;
+        let rpc_client = Data::new(
+            RpcClient::<WagerResponse>::new(
+                &connection,
+                &configuration.rabbitmq.exchange_name,
+                ExchangeKind::Direct,
+            )
+            .await?,
+        );
+        let shutdown_timeout_secs = configuration.application.shutdown_timeout_secs;
+        let (server, admin_server) = run(
             listener,
+            admin_listener,
             configuration.application.base_url,
-            configuration.rabbitmq,
-        )
-        .await?;
+            rpc_client.clone(),
+            configuration.batch,
+            services,
+            shutdown_timeout_secs + RPC_SHUTDOWN_GRACE_SECS,
+        )?;
 
-        Ok(Self { port, server })
+        Ok(Self {
+            port,
+            server,
+            admin_server,
+            connection,
+            rpc_client,
+            shutdown_timeout: Duration::from_secs(shutdown_timeout_secs),
+        })
     }
 
     pub fn port(&self) -> u16 {
         self.port
     }
 
+    /// Serves until SIGTERM or Ctrl-C, then stops accepting connections and lets in-flight
+    /// requests finish. RPCs still waiting on the engine after the shutdown timeout are
+    /// failed so their requests get a 503 instead of being dropped.
     pub async fn run_until_stopped(self) -> Result<(), std::io::Error> {
-        self.server.await
+        let server_handle = self.server.handle();
+        let admin_handle = self.admin_server.handle();
+        let rpc_client = self.rpc_client.clone();
+        let shutdown_timeout = self.shutdown_timeout;
+        tokio::spawn(async move {
+            shutdown_signal().await;
+            tracing::info!("Shutdown signal received, draining in-flight requests");
+            tokio::spawn(server_handle.stop(true));
+            tokio::spawn(admin_handle.stop(true));
+            tokio::time::sleep(shutdown_timeout).await;
+            tracing::warn!("Shutdown timeout elapsed, failing pending RPCs");
+            rpc_client.shutdown();
+        });
+
+        tokio::try_join!(self.server, self.admin_server)?;
+        self.rpc_client.shutdown();
+        self.connection.close().await;
+        Ok(())
     }
 }
 
 pub struct ApplicationBaseUrl(pub String);
 
-async fn run(
+/// Services shared by every worker through app data.
+struct AppServices {
+    api_key_store: Arc<dyn ApiKeyStore>,
+    signature_verifier: SignatureVerifier,
+    rate_limiter: RateLimiter,
+    storage_client: StorageClient,
+    status_store: Arc<dyn WagerStatusStore>,
+    callback_client: CallbackClient,
+    maintenance: Maintenance,
+}
+
+impl AppServices {
+    async fn build(configuration: &Config) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            api_key_store: build_api_key_store(
+                &configuration.auth,
+                configuration.postgres.as_ref(),
+            )
+            .await?,
+            signature_verifier: build_signature_verifier(
+                &configuration.signing,
+                configuration.redis.as_ref(),
+            )
+            .await?,
+            rate_limiter: build_rate_limiter(
+                &configuration.rate_limit,
+                configuration.redis.as_ref(),
+            )
+            .await?,
+            storage_client: StorageClient::new(&configuration.storage)?,
+            status_store: build_status_store(
+                &configuration.async_wagers,
+                configuration.redis.as_ref(),
+            )
+            .await?,
+            callback_client: CallbackClient::new(Duration::from_millis(
+                configuration.async_wagers.callback_timeout_ms,
+            ))?,
+            maintenance: build_maintenance(
+                &configuration.maintenance,
+                configuration.redis.as_ref(),
+            )
+            .await?,
+        })
+    }
+}
+
+async fn build_api_key_store(
+    auth_config: &AuthConfig,
+    postgres_config: Option<&PostgresConfig>,
+) -> Result<Arc<dyn ApiKeyStore>, anyhow::Error> {
+    match auth_config.source {
+        ApiKeySource::Config => Ok(Arc::new(ConfigApiKeyStore::new(&auth_config.keys))),
+        ApiKeySource::Postgres => {
+            let postgres_config = postgres_config
+                .context("`postgres` settings are required when `auth.source` is `postgres`")?;
+            let pool = sqlx::postgres::PgPoolOptions::new()
+                .max_connections(5)
+                .connect_lazy(&postgres_config.build_url())
+                .context("Failed to create Postgres pool for API keys")?;
+            Ok(Arc::new(PostgresApiKeyStore::new(pool)))
+        }
+    }
+}
+
+async fn build_signature_verifier(
+    signing_config: &SigningConfig,
+    redis_config: Option<&RedisConfig>,
+) -> Result<SignatureVerifier, anyhow::Error> {
+    anyhow::ensure!(
+        !signing_config.enabled || !signing_config.operators.is_empty(),
+        "`signing.operators` must not be empty when signing is enabled"
+    );
+    let nonces: Arc<dyn NonceStore> = match signing_config.nonce_backend {
+        StoreBackend::Memory => Arc::new(InMemoryNonceStore::new()),
+        StoreBackend::Redis => {
+            let redis_config = redis_config
+                .context("`redis` settings are required when `signing.nonce_backend` is `redis`")?;
+            Arc::new(RedisNonceStore::new(redis_config.uri.expose_secret()).await?)
+        }
+    };
+    Ok(SignatureVerifier::new(signing_config, nonces))
+}
+
+async fn build_rate_limiter(
+    rate_limit_config: &RateLimitConfig,
+    redis_config: Option<&RedisConfig>,
+) -> Result<RateLimiter, anyhow::Error> {
+    let store: Arc<dyn BucketStore> = match rate_limit_config.backend {
+        StoreBackend::Memory => Arc::new(InMemoryBucketStore::new()),
+        StoreBackend::Redis => {
+            let redis_config = redis_config
+                .context("`redis` settings are required when `rate_limit.backend` is `redis`")?;
+            Arc::new(RedisBucketStore::new(redis_config.uri.expose_secret()).await?)
+        }
+    };
+    Ok(RateLimiter::new(store, rate_limit_config))
+}
+
+async fn build_status_store(
+    async_wagers_config: &AsyncWagersConfig,
+    redis_config: Option<&RedisConfig>,
+) -> Result<Arc<dyn WagerStatusStore>, anyhow::Error> {
+    let ttl = Duration::from_secs(async_wagers_config.result_ttl_secs);
+    match async_wagers_config.backend {
+        StoreBackend::Memory => Ok(Arc::new(InMemoryWagerStatusStore::new(ttl))),
+        StoreBackend::Redis => {
+            let redis_config = redis_config
+                .context("`redis` settings are required when `async_wagers.backend` is `redis`")?;
+            Ok(Arc::new(
+                RedisWagerStatusStore::new(redis_config.uri.expose_secret(), ttl).await?,
+            ))
+        }
+    }
+}
+
+async fn build_maintenance(
+    maintenance_config: &MaintenanceConfig,
+    redis_config: Option<&RedisConfig>,
+) -> Result<Maintenance, anyhow::Error> {
+    let store: Arc<dyn MaintenanceStore> = match maintenance_config.backend {
+        StoreBackend::Memory => Arc::new(InMemoryMaintenanceStore::new()),
+        StoreBackend::Redis => {
+            let redis_config = redis_config
+                .context("`redis` settings are required when `maintenance.backend` is `redis`")?;
+            Arc::new(RedisMaintenanceStore::new(redis_config.uri.expose_secret()).await?)
+        }
+    };
+    Ok(Maintenance::new(store, maintenance_config))
+}
+
+/// Resolves on SIGTERM (sent by Docker and Kubernetes) or Ctrl-C.
+async fn shutdown_signal() {
+    let mut terminate = signal(SignalKind::terminate()).expect("Failed to install SIGTERM handler");
+    tokio::select! {
+        _ = terminate.recv() => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
+}
+
+/// Builds the public API server and the internal admin server, which share the services.
+fn run(
     listener: TcpListener,
+    admin_listener: TcpListener,
     base_url: String,
-    rabbitmq_config: RabbitMqConfig,
-) -> Result<Server, anyhow::Error> {
+    rpc_client: Data<RpcClient<WagerResponse>>,
+    batch_config: BatchConfig,
+    services: AppServices,
+    shutdown_timeout_secs: u64,
+) -> Result<(Server, Server), anyhow::Error> {
     let base_url = Data::new(ApplicationBaseUrl(base_url));
+    // Batches are far larger than single wagers, and signed requests buffer the raw body.
+    let json_config = JsonConfig::default().limit(batch_config.max_body_bytes);
+    let payload_config = PayloadConfig::new(batch_config.max_body_bytes);
+    let batch_config = Data::new(batch_config);
+    let api_key_store: Data<dyn ApiKeyStore> = Data::from(services.api_key_store);
+    let signature_verifier = Data::new(services.signature_verifier);
+    let rate_limiter = Data::new(services.rate_limiter);
+    let storage_client = Data::new(services.storage_client);
+    let status_store: Data<dyn WagerStatusStore> = Data::from(services.status_store);
+    let callback_client = Data::new(services.callback_client);
+    let maintenance = Data::new(services.maintenance);
 
-    let conn = RabbitConnection::new(&rabbitmq_config.uri).await?;
-
-    let rpc_client = RpcClient::<WagerResponse>::new(
-        &conn,
-        &rabbitmq_config.exchange_name,
-        ExchangeKind::Direct,
-    )
-    .await?;
-    let rpc_client = Data::new(rpc_client);
-
+    let admin_server = run_admin(admin_listener, maintenance.clone())?;
     let server = HttpServer::new(move || {
         App::new()
             .configure(routes::init)
             .app_data(base_url.clone())
             .app_data(rpc_client.clone())
+            .app_data(api_key_store.clone())
+            .app_data(signature_verifier.clone())
+            .app_data(rate_limiter.clone())
+            .app_data(storage_client.clone())
+            .app_data(status_store.clone())
+            .app_data(callback_client.clone())
+            .app_data(maintenance.clone())
+            .app_data(batch_config.clone())
+            .app_data(json_config.clone())
+            .app_data(payload_config.clone())
+    })
+    .disable_signals()
+    .shutdown_timeout(shutdown_timeout_secs)
+    .listen(listener)?
+    .run();
+
+    Ok((server, admin_server))
+}
+
+/// Serves `/metrics` and `/admin/maintenance` on their own listener so they are not reachable
+/// through the public API port.
+fn run_admin(
+    listener: TcpListener,
+    maintenance: Data<Maintenance>,
+) -> Result<Server, anyhow::Error> {
+    let server = HttpServer::new(move || {
+        App::new()
+            .configure(routes::init_admin)
+            .app_data(maintenance.clone())
     })
+    .workers(1)
+    .disable_signals()
     .listen(listener)?
     .run();
 
@@ -0,0 +1,38 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use super::ApiKeyStore;
+use crate::{configuration::ApiKeyConfig, domain::models::ApiKey};
+
+/// Serves API keys declared in the gateway configuration files.
+pub struct ConfigApiKeyStore {
+    keys: HashMap<String, Vec<ApiKey>>,
+}
+
+impl ConfigApiKeyStore {
+    pub fn new(keys: &[ApiKeyConfig]) -> Self {
+        let mut by_hash: HashMap<String, Vec<ApiKey>> = HashMap::new();
+        for key in keys {
+            by_hash
+                .entry(key.key_hash.to_lowercase())
+                .or_default()
+                .push(ApiKey {
+                    id: key.id.clone(),
+                    site_ids: key.site_ids.clone(),
+                    not_before: key.not_before,
+                    expires_at: key.expires_at,
+                    callback_urls: key.callback_urls.clone(),
+                    callback_secret: key.callback_secret.clone(),
+                });
+        }
+        Self { keys: by_hash }
+    }
+}
+
+#[async_trait]
+impl ApiKeyStore for ConfigApiKeyStore {
+    async fn find_by_hash(&self, key_hash: &str) -> anyhow::Result<Vec<ApiKey>> {
+        Ok(self.keys.get(key_hash).cloned().unwrap_or_default())
+    }
+}
@@ -0,0 +1,20 @@
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+
+use crate::domain::models::ApiKey;
+
+pub mod config_store;
+pub mod nonces;
+pub mod postgres_store;
+pub mod signature;
+
+#[async_trait]
+pub trait ApiKeyStore: Send + Sync {
+    /// Looks up every key whose stored hash matches `key_hash`, regardless of validity window.
+    async fn find_by_hash(&self, key_hash: &str) -> anyhow::Result<Vec<ApiKey>>;
+}
+
+/// Hashes a raw API key the same way keys are stored: lowercase hex SHA-256.
+pub fn hash_api_key(raw_key: &str) -> String {
+    hex::encode(Sha256::digest(raw_key.as_bytes()))
+}
@@ -0,0 +1,68 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+
+use super::NonceStore;
+
+/// How often nonces past their replay window are forgotten.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Keeps nonces in process memory; a nonce is only rejected by the replica that saw it.
+pub struct InMemoryNonceStore {
+    state: Mutex<Nonces>,
+}
+
+struct Nonces {
+    // (operator id, nonce) -> when it can be forgotten.
+    seen: HashMap<(String, String), Instant>,
+    next_sweep: Instant,
+}
+
+impl InMemoryNonceStore {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(Nonces {
+                seen: HashMap::new(),
+                next_sweep: Instant::now() + SWEEP_INTERVAL,
+            }),
+        }
+    }
+}
+
+impl Default for InMemoryNonceStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl NonceStore for InMemoryNonceStore {
+    async fn remember(
+        &self,
+        operator_id: &str,
+        nonce: &str,
+        ttl: Duration,
+    ) -> anyhow::Result<bool> {
+        let now = Instant::now();
+        let mut state = self.state.lock().expect("nonce cache poisoned");
+        if now >= state.next_sweep {
+            state.seen.retain(|_, forget_at| *forget_at > now);
+            state.next_sweep = now + SWEEP_INTERVAL;
+        }
+
+        let key = (operator_id.to_string(), nonce.to_string());
+        if state
+            .seen
+            .get(&key)
+            .is_some_and(|forget_at| *forget_at > now)
+        {
+            return Ok(false);
+        }
+        state.seen.insert(key, now + ttl);
+        Ok(true)
+    }
+}
@@ -0,0 +1,14 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+pub mod in_memory;
+pub mod redis_store;
+
+/// Remembers the nonces of signed requests for as long as they could be replayed.
+#[async_trait]
+pub trait NonceStore: Send + Sync {
+    /// Records `nonce` for `operator_id` for `ttl`; returns `false` if it was already recorded.
+    async fn remember(&self, operator_id: &str, nonce: &str, ttl: Duration)
+    -> anyhow::Result<bool>;
+}
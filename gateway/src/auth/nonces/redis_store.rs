@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use redis::{AsyncCommands, SetExpiry, SetOptions, aio::ConnectionManager};
+
+use super::NonceStore;
+
+/// Keeps nonces in Redis so a request replayed against another gateway replica is
+/// rejected too.
+pub struct RedisNonceStore {
+    redis: ConnectionManager,
+}
+
+impl RedisNonceStore {
+    pub async fn new(redis_url: &str) -> anyhow::Result<Self> {
+        let client = redis::Client::open(redis_url)?;
+        let redis = ConnectionManager::new(client).await?;
+        Ok(Self { redis })
+    }
+}
+
+#[async_trait]
+impl NonceStore for RedisNonceStore {
+    async fn remember(
+        &self,
+        operator_id: &str,
+        nonce: &str,
+        ttl: Duration,
+    ) -> anyhow::Result<bool> {
+        let options = SetOptions::default()
+            .conditional_set(redis::ExistenceCheck::NX)
+            .with_expiration(SetExpiry::PX(ttl.as_millis().max(1) as u64));
+        let stored: Option<String> = self
+            .redis
+            .clone()
+            .set_options(format!("nonce:{operator_id}:{nonce}"), 1, options)
+            .await?;
+        Ok(stored.is_some())
+    }
+}
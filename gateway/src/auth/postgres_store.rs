@@ -0,0 +1,59 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use secrecy::SecretString;
+use sqlx::PgPool;
+use tracing::instrument;
+
+use super::ApiKeyStore;
+use crate::domain::models::ApiKey;
+
+/// Serves API keys from the `api_keys` table, so keys can be issued and rotated
+/// without restarting the gateway.
+pub struct PostgresApiKeyStore {
+    pool: PgPool,
+}
+
+impl PostgresApiKeyStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ApiKeyRow {
+    id: String,
+    site_ids: Vec<i32>,
+    not_before: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    callback_urls: Vec<String>,
+    callback_secret: Option<String>,
+}
+
+#[async_trait]
+impl ApiKeyStore for PostgresApiKeyStore {
+    #[instrument(name = "api_keys.find_by_hash", skip(self, key_hash))]
+    async fn find_by_hash(&self, key_hash: &str) -> anyhow::Result<Vec<ApiKey>> {
+        let rows: Vec<ApiKeyRow> = sqlx::query_as(
+            r#"
+            SELECT id, site_ids, not_before, expires_at, callback_urls, callback_secret
+            FROM api_keys
+            WHERE key_hash = $1
+            "#,
+        )
+        .bind(key_hash)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ApiKey {
+                id: row.id,
+                site_ids: row.site_ids,
+                not_before: row.not_before,
+                expires_at: row.expires_at,
+                callback_urls: row.callback_urls,
+                callback_secret: row.callback_secret.map(SecretString::from),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,141 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use hmac::{Hmac, Mac};
+use secrecy::{ExposeSecret, SecretString};
+use sha2::Sha256;
+use tracing::error;
+
+use super::nonces::NonceStore;
+use crate::configuration::SigningConfig;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SignatureError {
+    #[error("Missing {0} header")]
+    MissingHeader(&'static str),
+    #[error("Unknown operator")]
+    UnknownOperator,
+    #[error("Operator does not match the API key")]
+    OperatorMismatch,
+    #[error("Malformed timestamp")]
+    MalformedTimestamp,
+    #[error("Timestamp is outside the allowed window")]
+    StaleTimestamp,
+    #[error("Invalid signature")]
+    InvalidSignature,
+    #[error("Nonce has already been used")]
+    ReplayedNonce,
+    #[error("Failed to check nonce")]
+    NonceStoreUnavailable,
+}
+
+/// The parts of an incoming request covered by the HMAC signature.
+pub struct SignedRequest<'a> {
+    /// Operator the authenticated API key was issued to; must be the signing operator.
+    pub api_key_id: &'a str,
+    pub operator_id: &'a str,
+    pub timestamp: &'a str,
+    pub nonce: &'a str,
+    pub signature: &'a str,
+    pub method: &'a str,
+    pub path: &'a str,
+    pub body: &'a [u8],
+}
+
+/// Verifies HMAC-SHA256 request signatures and rejects replayed nonces.
+pub struct SignatureVerifier {
+    enabled: bool,
+    max_clock_skew_secs: i64,
+    secrets: HashMap<String, SecretString>,
+    nonces: Arc<dyn NonceStore>,
+}
+
+impl SignatureVerifier {
+    pub fn new(config: &SigningConfig, nonces: Arc<dyn NonceStore>) -> Self {
+        Self {
+            enabled: config.enabled,
+            max_clock_skew_secs: config.max_clock_skew_secs as i64,
+            secrets: config
+                .operators
+                .iter()
+                .map(|operator| (operator.id.clone(), operator.secret.clone()))
+                .collect(),
+            nonces,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Checks the signature of `request` against the operator's secret at unix time `now`.
+    ///
+    /// The nonce is only recorded once the signature is valid, so unsigned traffic
+    /// cannot burn nonces belonging to a legitimate operator.
+    pub async fn verify(
+        &self,
+        request: &SignedRequest<'_>,
+        now: i64,
+    ) -> Result<(), SignatureError> {
+        // One operator's secret must not sign requests made with another operator's key.
+        if request.operator_id != request.api_key_id {
+            return Err(SignatureError::OperatorMismatch);
+        }
+        let secret = self
+            .secrets
+            .get(request.operator_id)
+            .ok_or(SignatureError::UnknownOperator)?;
+
+        let timestamp: i64 = request
+            .timestamp
+            .parse()
+            .map_err(|_| SignatureError::MalformedTimestamp)?;
+        if (now - timestamp).abs() > self.max_clock_skew_secs {
+            return Err(SignatureError::StaleTimestamp);
+        }
+
+        let signature =
+            hex::decode(request.signature).map_err(|_| SignatureError::InvalidSignature)?;
+        let mut mac = HmacSha256::new_from_slice(secret.expose_secret().as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(&signing_payload(
+            request.method,
+            request.path,
+            request.timestamp,
+            request.nonce,
+            request.body,
+        ));
+        mac.verify_slice(&signature)
+            .map_err(|_| SignatureError::InvalidSignature)?;
+
+        // Any replay after the window closes fails the timestamp check instead.
+        let ttl = Duration::from_secs((timestamp + self.max_clock_skew_secs - now).max(1) as u64);
+        match self
+            .nonces
+            .remember(request.operator_id, request.nonce, ttl)
+            .await
+        {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(SignatureError::ReplayedNonce),
+            Err(e) => {
+                error!("Failed to record nonce: {:?}", e);
+                Err(SignatureError::NonceStoreUnavailable)
+            }
+        }
+    }
+}
+
+/// Builds the byte string that operators sign:
+/// `METHOD\npath?query\ntimestamp\nnonce\n` followed by the raw request body.
+pub fn signing_payload(
+    method: &str,
+    path: &str,
+    timestamp: &str,
+    nonce: &str,
+    body: &[u8],
+) -> Vec<u8> {
+    let mut payload = format!("{method}\n{path}\n{timestamp}\n{nonce}\n").into_bytes();
+    payload.extend_from_slice(body);
+    payload
+}
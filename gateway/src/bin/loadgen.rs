@@ -0,0 +1,326 @@
+//! Drives `POST /api/v1/wager` at a target rate and reports latency percentiles, HTTP
+//! statuses, engine error codes and win counts as JSON.
+//!
+//! Wagers are replayed from a JSONL file of `WagerRequest`s, or synthesized from a profile
+//! of sites, users, games and amounts when no file is given.
+//!
+//! ```sh
+//! cargo run --release -p gateway --bin loadgen -- --input wagers.jsonl --rps 200 --api-key $KEY
+//! cargo run --release -p gateway --bin loadgen -- --requests 10000 --sites 1,2,3 --concurrency 64
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, ensure};
+use clap::Parser;
+use gateway::{
+    auth::signature::signing_payload,
+    domain::models::{WagerRequest, WagerResponse},
+    middleware::{
+        api_key::API_KEY_HEADER,
+        signature::{NONCE_HEADER, OPERATOR_ID_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER},
+    },
+};
+use hmac::{Hmac, Mac};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde::Serialize;
+use sha2::Sha256;
+use tokio::{sync::Semaphore, task::JoinSet, time::MissedTickBehavior};
+use uuid::Uuid;
+
+const WAGER_PATH: &str = "/api/v1/wager";
+
+#[derive(Parser)]
+struct Args {
+    #[arg(long, default_value = "http://127.0.0.1:8080")]
+    url: String,
+    /// JSONL file with one `WagerRequest` per line; wagers are synthesized if not set.
+    #[arg(long)]
+    input: Option<PathBuf>,
+    /// Wagers to send; defaults to every line of `--input` once. Lines are reused in
+    /// order when more wagers than lines are asked for.
+    #[arg(long)]
+    requests: Option<usize>,
+    /// Wagers started per second; unlimited if 0.
+    #[arg(long, default_value_t = 100)]
+    rps: u32,
+    /// Wagers in flight at once.
+    #[arg(long, default_value_t = 32)]
+    concurrency: usize,
+    #[arg(long, default_value_t = 10_000)]
+    timeout_ms: u64,
+    #[arg(long)]
+    api_key: Option<String>,
+    /// Signs every request as this operator; needs `--secret`.
+    #[arg(long, requires = "secret")]
+    operator_id: Option<String>,
+    #[arg(long, requires = "operator_id")]
+    secret: Option<String>,
+    /// Profile: sites to spread synthesized wagers over.
+    #[arg(long, value_delimiter = ',', default_value = "1")]
+    sites: Vec<i32>,
+    /// Profile: users per site, numbered from 1.
+    #[arg(long, default_value_t = 1_000)]
+    users: i32,
+    /// Profile: games, numbered from 1.
+    #[arg(long, default_value_t = 10)]
+    games: i32,
+    #[arg(long, default_value_t = 100)]
+    min_amount: u64,
+    #[arg(long, default_value_t = 10_000)]
+    max_amount: u64,
+    /// Seed for synthesized wagers, so runs can be repeated.
+    #[arg(long, default_value_t = 1)]
+    seed: u64,
+}
+
+/// Where the wagers to send come from.
+enum Source {
+    Replay(Vec<WagerRequest>),
+    Profile(Box<StdRng>),
+}
+
+impl Source {
+    fn wager(&mut self, i: usize, args: &Args) -> WagerRequest {
+        match self {
+            Source::Replay(wagers) => {
+                let wager = &wagers[i % wagers.len()];
+                WagerRequest {
+                    id: wager.id,
+                    amount: wager.amount,
+                    site_id: wager.site_id,
+                    user_id: wager.user_id,
+                    game_id: wager.game_id,
+                    currency: wager.currency.clone(),
+                    received_at: None,
+                    cheat_code: wager.cheat_code.clone(),
+                    callback_url: None,
+                }
+            }
+            Source::Profile(rng) => WagerRequest {
+                id: None,
+                amount: rng.random_range(args.min_amount..=args.max_amount),
+                site_id: args.sites[rng.random_range(0..args.sites.len())],
+                user_id: rng.random_range(1..=args.users),
+                game_id: rng.random_range(1..=args.games),
+                currency: None,
+                received_at: None,
+                cheat_code: None,
+                callback_url: None,
+            },
+        }
+    }
+}
+
+/// What came back for one wager.
+struct Outcome {
+    latency: Duration,
+    /// HTTP status, or `"timeout"` / `"transport"` if no response arrived.
+    status: String,
+    response: Option<WagerResponse>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    requests: usize,
+    elapsed_secs: f64,
+    achieved_rps: f64,
+    /// Responses by HTTP status.
+    statuses: BTreeMap<String, usize>,
+    /// Engine error codes of rejected wagers, e.g. `pool_paused`.
+    error_codes: BTreeMap<String, usize>,
+    wins: usize,
+    losses: usize,
+    latency_ms: Latency,
+}
+
+#[derive(Serialize)]
+struct Latency {
+    mean: f64,
+    p50: f64,
+    p90: f64,
+    p95: f64,
+    p99: f64,
+    max: f64,
+}
+
+impl Latency {
+    fn new(mut samples: Vec<Duration>) -> Self {
+        samples.sort_unstable();
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let percentile = |p: f64| {
+            samples
+                .get(((samples.len() as f64 * p).ceil() as usize).max(1) - 1)
+                .map_or(0.0, |&d| ms(d))
+        };
+        Self {
+            mean: if samples.is_empty() {
+                0.0
+            } else {
+                samples.iter().map(|&d| ms(d)).sum::<f64>() / samples.len() as f64
+            },
+            p50: percentile(0.50),
+            p90: percentile(0.90),
+            p95: percentile(0.95),
+            p99: percentile(0.99),
+            max: samples.last().map_or(0.0, |&d| ms(d)),
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Arc::new(Args::parse());
+    ensure!(!args.sites.is_empty(), "--sites must not be empty");
+    ensure!(
+        args.users >= 1 && args.games >= 1,
+        "--users and --games must be at least 1"
+    );
+    ensure!(
+        args.min_amount <= args.max_amount,
+        "--min-amount must not exceed --max-amount"
+    );
+
+    let mut source = match &args.input {
+        Some(path) => Source::Replay(read_wagers(path)?),
+        None => Source::Profile(Box::new(StdRng::seed_from_u64(args.seed))),
+    };
+    let requests = match (&source, args.requests) {
+        (_, Some(requests)) => requests,
+        (Source::Replay(wagers), None) => wagers.len(),
+        (Source::Profile(_), None) => 1_000,
+    };
+    if let Source::Replay(wagers) = &source {
+        ensure!(
+            !wagers.is_empty() || requests == 0,
+            "{} has no wagers",
+            args.input.as_ref().expect("replay has an input").display()
+        );
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_millis(args.timeout_ms))
+        .build()?;
+    let url = format!("{}{}", args.url.trim_end_matches('/'), WAGER_PATH);
+    let in_flight = Arc::new(Semaphore::new(args.concurrency.max(1)));
+    let mut ticker = (args.rps > 0).then(|| {
+        let mut ticker = tokio::time::interval(Duration::from_secs_f64(1.0 / args.rps as f64));
+        // A stalled gateway should not be hit with the missed wagers all at once.
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ticker
+    });
+
+    let started = Instant::now();
+    let mut tasks = JoinSet::new();
+    for i in 0..requests {
+        if let Some(ticker) = &mut ticker {
+            ticker.tick().await;
+        }
+        let permit = in_flight.clone().acquire_owned().await?;
+        let body = serde_json::to_vec(&source.wager(i, &args))?;
+        let (client, url, args) = (client.clone(), url.clone(), args.clone());
+        tasks.spawn(async move {
+            let outcome = send(&client, &url, &args, body).await;
+            drop(permit);
+            outcome
+        });
+    }
+    let mut outcomes = Vec::with_capacity(requests);
+    while let Some(outcome) = tasks.join_next().await {
+        outcomes.push(outcome?);
+    }
+    let elapsed = started.elapsed();
+
+    let mut report = Report {
+        requests,
+        elapsed_secs: elapsed.as_secs_f64(),
+        achieved_rps: requests as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        statuses: BTreeMap::new(),
+        error_codes: BTreeMap::new(),
+        wins: 0,
+        losses: 0,
+        latency_ms: Latency::new(outcomes.iter().map(|outcome| outcome.latency).collect()),
+    };
+    for outcome in outcomes {
+        *report.statuses.entry(outcome.status).or_default() += 1;
+        let Some(response) = outcome.response else {
+            continue;
+        };
+        match (response.status.as_str(), response.error) {
+            (_, Some(code)) => *report.error_codes.entry(code).or_default() += 1,
+            // The engine reports wins as status "true".
+            ("true", None) => report.wins += 1,
+            (_, None) => report.losses += 1,
+        }
+    }
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+fn read_wagers(path: &PathBuf) -> anyhow::Result<Vec<WagerRequest>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read wagers from {}", path.display()))?;
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .with_context(|| format!("{}:{}: not a wager request", path.display(), i + 1))
+        })
+        .collect()
+}
+
+async fn send(client: &reqwest::Client, url: &str, args: &Args, body: Vec<u8>) -> Outcome {
+    let mut request = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json");
+    if let Some(api_key) = &args.api_key {
+        request = request.header(API_KEY_HEADER, api_key);
+    }
+    if let (Some(operator_id), Some(secret)) = (&args.operator_id, &args.secret) {
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        let nonce = Uuid::new_v4().to_string();
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(&signing_payload(
+            "POST", WAGER_PATH, &timestamp, &nonce, &body,
+        ));
+        request = request
+            .header(OPERATOR_ID_HEADER, operator_id)
+            .header(TIMESTAMP_HEADER, timestamp)
+            .header(NONCE_HEADER, nonce)
+            .header(SIGNATURE_HEADER, hex::encode(mac.finalize().into_bytes()));
+    }
+
+    let started = Instant::now();
+    let response = match request.body(body).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            return Outcome {
+                latency: started.elapsed(),
+                status: if e.is_timeout() {
+                    "timeout"
+                } else {
+                    "transport"
+                }
+                .to_string(),
+                response: None,
+            };
+        }
+    };
+    let status = response.status().as_u16().to_string();
+    let response = response.json::<WagerResponse>().await.ok();
+    Outcome {
+        latency: started.elapsed(),
+        status,
+        response,
+    }
+}
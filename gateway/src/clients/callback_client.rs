@@ -0,0 +1,156 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Context;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect,
+};
+use secrecy::{ExposeSecret, SecretString};
+use sha2::Sha256;
+use tracing::instrument;
+use url::{Host, Url};
+
+use crate::domain::models::WagerStatus;
+
+pub const SIGNATURE_HEADER: &str = "X-Jackpot-Signature";
+
+/// Delivers the final status of asynchronous wagers to the operator's `callback_url`.
+///
+/// Only URLs registered for the submitting API key are called (see
+/// [`ApiKey::allows_callback`](crate::domain::models::ApiKey::allows_callback)). Hosts that
+/// resolve to private, loopback or otherwise internal addresses are refused and redirects
+/// are not followed, so a callback cannot be pointed at the gateway's own network.
+pub struct CallbackClient {
+    http: reqwest::Client,
+}
+
+impl CallbackClient {
+    pub fn new(timeout: Duration) -> anyhow::Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(timeout)
+            .redirect(redirect::Policy::none())
+            .no_proxy()
+            .dns_resolver(Arc::new(PublicResolver))
+            .build()
+            .context("Failed to build callback HTTP client")?;
+        Ok(Self { http })
+    }
+
+    /// Posts `status` to `callback_url`, signed like storage's webhooks: the
+    /// `X-Jackpot-Signature` header carries `t={timestamp},v1={hex HMAC-SHA256}` of
+    /// `"{timestamp}.{body}"` with the API key's callback secret.
+    #[instrument(name = "callback.notify", skip(self, secret, status), fields(wager_id = %status.wager_id))]
+    pub async fn notify(
+        &self,
+        callback_url: &str,
+        secret: &SecretString,
+        status: &WagerStatus,
+    ) -> anyhow::Result<()> {
+        let url = Url::parse(callback_url).context("Invalid callback URL")?;
+        // IP literals never reach the resolver, so they are checked here.
+        let literal = match url.host() {
+            Some(Host::Ipv4(ip)) => Some(IpAddr::V4(ip)),
+            Some(Host::Ipv6(ip)) => Some(IpAddr::V6(ip)),
+            _ => None,
+        };
+        anyhow::ensure!(
+            literal.is_none_or(is_public),
+            "Callback URL points at a non-public address"
+        );
+
+        let body = serde_json::to_vec(status)?;
+        let timestamp = Utc::now().timestamp();
+        let response = self
+            .http
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(
+                SIGNATURE_HEADER,
+                format!(
+                    "t={},v1={}",
+                    timestamp,
+                    sign(secret.expose_secret(), timestamp, &body)
+                ),
+            )
+            .body(body)
+            .send()
+            .await
+            .context("Failed to reach callback URL")?;
+
+        // Redirects are not followed, so anything but 2xx is a failed delivery.
+        anyhow::ensure!(
+            response.status().is_success(),
+            "Callback URL returned {}",
+            response.status()
+        );
+        Ok(())
+    }
+}
+
+/// Hex HMAC-SHA256 of `"{timestamp}.{body}"` with the API key's callback secret.
+fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{timestamp}.").as_bytes());
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Resolves callback hosts with the system resolver and fails if any address is not
+/// public, so DNS cannot be used to reach internal services.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            if addrs.iter().any(|addr| !is_public(addr.ip())) {
+                return Err(format!("{} resolves to a non-public address", name.as_str()).into());
+            }
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_v4(ip),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // 0.0.0.0/8, shared address space 100.64.0.0/10, benchmarking 198.18.0.0/15
+        // and the reserved 240.0.0.0/4.
+        || a == 0
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 198 && (18..20).contains(&b))
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        || ip.is_unique_local()
+        || ip.is_unicast_link_local())
+}
@@ -0,0 +1,2 @@
+pub mod callback_client;
+pub mod storage_client;
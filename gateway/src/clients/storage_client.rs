@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use reqwest::StatusCode;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{
+    configuration::StorageConfig,
+    domain::models::{WagerListQuery, WagerPage, WagerRecord},
+};
+
+/// HTTP client for the storage service's read API.
+pub struct StorageClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl StorageClient {
+    pub fn new(config: &StorageConfig) -> anyhow::Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .context("Failed to build storage HTTP client")?;
+        Ok(Self {
+            http,
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    #[instrument(name = "storage.get_wager", skip(self))]
+    pub async fn get_wager(&self, id: Uuid) -> anyhow::Result<Option<WagerRecord>> {
+        let response = self
+            .http
+            .get(format!("{}/wagers/{}", self.base_url, id))
+            .send()
+            .await
+            .context("Failed to reach storage")?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let wager = response
+            .error_for_status()
+            .context("Storage returned an error")?
+            .json()
+            .await
+            .context("Failed to decode wager from storage")?;
+        Ok(Some(wager))
+    }
+
+    #[instrument(name = "storage.list_wagers", skip(self))]
+    pub async fn list_wagers(&self, query: &WagerListQuery) -> anyhow::Result<WagerPage> {
+        self.http
+            .get(format!("{}/wagers", self.base_url))
+            .query(query)
+            .send()
+            .await
+            .context("Failed to reach storage")?
+            .error_for_status()
+            .context("Storage returned an error")?
+            .json()
+            .await
+            .context("Failed to decode wagers from storage")
+    }
+}
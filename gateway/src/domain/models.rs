@@ -1,3 +1,5 @@
+use chrono::{DateTime, Utc};
+use secrecy::SecretString;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -8,14 +10,190 @@ pub struct WagerRequest {
     pub site_id: i32,
     pub user_id: i32,
     pub game_id: i32,
+    /// ISO 4217 code of `amount`, e.g. `EUR`.
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// When the gateway accepted the wager. Set by the gateway, never taken from callers.
+    #[serde(default, skip_deserializing)]
+    pub received_at: Option<DateTime<Utc>>,
 
     pub cheat_code: Option<String>,
+
+    /// Notified with the final [`WagerStatus`] of an asynchronous submission. Must be one
+    /// of the API key's registered callback URLs. Never forwarded to the engine.
+    #[serde(default, skip_serializing)]
+    pub callback_url: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+impl WagerRequest {
+    /// Checks the fields that can be validated without contacting the engine.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.amount == 0 {
+            return Err("amount must be greater than zero".to_string());
+        }
+        let is_iso_code =
+            |code: &str| code.len() == 3 && code.bytes().all(|b| b.is_ascii_uppercase());
+        if self
+            .currency
+            .as_deref()
+            .is_some_and(|code| !is_iso_code(code))
+        {
+            return Err("currency must be a three-letter ISO 4217 code".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct WagerResponse {
     #[serde(with = "uuid::serde::compact")]
     pub wager_id: Uuid,
     pub status: String,
     pub amount: u64,
+    /// Why the engine rejected the wager without playing it, e.g. `pool_paused`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// An authenticated API key, attached to the request by the API key middleware.
+#[derive(Debug, Clone)]
+pub struct ApiKey {
+    pub id: String,
+    pub site_ids: Vec<i32>,
+    pub not_before: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// URLs asynchronous results may be delivered to.
+    pub callback_urls: Vec<String>,
+    /// Signs callback payloads; callbacks are refused for keys without one.
+    pub callback_secret: Option<SecretString>,
+}
+
+impl ApiKey {
+    /// Returns true if the key is inside its validity window at `now`.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.not_before.is_none_or(|t| t <= now) && self.expires_at.is_none_or(|t| now < t)
+    }
+
+    pub fn allows_site(&self, site_id: i32) -> bool {
+        self.site_ids.contains(&site_id)
+    }
+
+    /// Returns the secret to sign callbacks with if `url` is registered for this key.
+    pub fn allows_callback(&self, url: &str) -> Option<&SecretString> {
+        self.callback_secret.as_ref().filter(|_| {
+            self.callback_urls
+                .iter()
+                .any(|registered| registered == url)
+        })
+    }
+}
+
+/// A stored wager, as served by the storage read API.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WagerRecord {
+    pub id: Uuid,
+    pub site_id: i32,
+    pub game_id: i32,
+    pub user_id: i32,
+    pub amount: f64,
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// `won` or `lost`; missing for wagers stored before outcomes were recorded.
+    #[serde(default)]
+    pub outcome: Option<String>,
+    #[serde(default)]
+    pub win_amount: Option<i64>,
+    #[serde(default)]
+    pub receipt_id: Option<String>,
+    pub received_at: Option<DateTime<Utc>>,
+    pub processed_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Filters for listing a user's wagers, newest first.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WagerListQuery {
+    pub site_id: i32,
+    pub user_id: i32,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WagerPage {
+    pub items: Vec<WagerRecord>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubmissionMode {
+    /// Wait for the engine's result before responding.
+    Sync,
+    /// Respond with 202 immediately; the result is polled or delivered to `callback_url`.
+    Async,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubmitOptions {
+    pub mode: Option<SubmissionMode>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "state", rename_all = "lowercase")]
+pub enum WagerState {
+    Pending,
+    Completed { result: WagerResponse },
+    Failed { error: String },
+}
+
+/// Progress of an asynchronously submitted wager.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WagerStatus {
+    pub wager_id: Uuid,
+    pub site_id: i32,
+    #[serde(flatten)]
+    pub state: WagerState,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WagerBatchRequest {
+    pub wagers: Vec<WagerRequest>,
+}
+
+/// Why a batch item was rejected before anything was published.
+#[derive(Debug, Serialize)]
+pub struct BatchItemError {
+    pub index: usize,
+    pub error: String,
+}
+
+/// Outcome of one wager of a batch, reported at the same index as the request.
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum BatchItemResult {
+    Ok {
+        wager_id: Uuid,
+        result: WagerResponse,
+    },
+    Error {
+        wager_id: Uuid,
+        error: String,
+    },
+    /// Refused by the rate limiter and not published; may be resubmitted after
+    /// `retry_after_secs`.
+    #[serde(rename = "rate_limited")]
+    RateLimited {
+        wager_id: Uuid,
+        retry_after_secs: u64,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub struct WagerBatchResponse {
+    pub results: Vec<BatchItemResult>,
 }
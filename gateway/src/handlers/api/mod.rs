@@ -1,2 +1,3 @@
 pub mod health;
 pub mod wager;
+pub mod wager_batch;
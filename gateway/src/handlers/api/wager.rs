@@ -1,23 +1,250 @@
 use crate::{
-    domain::models::{WagerRequest, WagerResponse},
-    messaging::rpc_client::RpcClient,
+    clients::{callback_client::CallbackClient, storage_client::StorageClient},
+    domain::models::{
+        ApiKey, SubmissionMode, SubmitOptions, WagerListQuery, WagerRequest, WagerResponse,
+        WagerState, WagerStatus,
+    },
+    handlers::maintenance::under_maintenance,
+    maintenance::Maintenance,
+    messaging::rpc_client::{RpcClient, RpcError},
+    metrics::{self, WAGERS_SUBMITTED},
+    rate_limit::RateLimiter,
+    wager_status::WagerStatusStore,
 };
-use actix_web::{HttpResponse, web};
+use actix_web::{HttpResponse, http::header, web};
+use chrono::Utc;
+use secrecy::SecretString;
 use uuid::Uuid;
 
 // POST / - Creates a wager and sends it to RabbitMQ
+// With `?mode=async` it responds 202 right away and the result is polled or called back.
+#[allow(clippy::too_many_arguments)]
 pub async fn create_wager(
     rpc_client: web::Data<RpcClient<WagerResponse>>,
+    rate_limiter: web::Data<RateLimiter>,
+    status_store: web::Data<dyn WagerStatusStore>,
+    callback_client: web::Data<CallbackClient>,
+    maintenance: web::Data<Maintenance>,
+    api_key: web::ReqData<ApiKey>,
+    options: web::Query<SubmitOptions>,
     request: web::Json<WagerRequest>,
 ) -> HttpResponse {
     let mut request = request.into_inner();
+    request.received_at = Some(Utc::now());
+
+    if !api_key.allows_site(request.site_id) {
+        tracing::warn!(
+            api_key_id = %api_key.id,
+            site_id = request.site_id,
+            "API key is not authorized for site"
+        );
+        return HttpResponse::Forbidden().json("API key is not authorized for this site");
+    }
+
+    if let Err(retry_after) = maintenance.check([request.site_id]).await {
+        tracing::warn!(
+            site_id = request.site_id,
+            "Wager rejected during maintenance"
+        );
+        return under_maintenance(retry_after);
+    }
+
+    if let Err(e) = request.validate() {
+        return HttpResponse::BadRequest().json(e);
+    }
+
+    let callback_secret = match &request.callback_url {
+        Some(url) => match api_key.allows_callback(url) {
+            Some(secret) => Some(secret.clone()),
+            None => {
+                return HttpResponse::BadRequest()
+                    .json("callback_url is not registered for this API key");
+            }
+        },
+        None => None,
+    };
+
+    if let Err(retry_after) = rate_limiter.check(request.site_id, request.user_id).await {
+        tracing::warn!(
+            site_id = request.site_id,
+            user_id = request.user_id,
+            "Wager rejected by rate limiter"
+        );
+        return HttpResponse::TooManyRequests()
+            .insert_header((header::RETRY_AFTER, retry_after.as_secs_f64().ceil() as u64))
+            .json("Rate limit exceeded");
+    }
 
     if request.id.is_none() {
         request.id = Some(Uuid::new_v4());
     }
 
+    if options.mode == Some(SubmissionMode::Async) {
+        WAGERS_SUBMITTED.with_label_values(&["async"]).inc();
+        return submit_async(
+            rpc_client,
+            status_store,
+            callback_client,
+            request,
+            callback_secret,
+        )
+        .await;
+    }
+
+    WAGERS_SUBMITTED.with_label_values(&["sync"]).inc();
     match rpc_client.call(&request).await {
-        Ok(response) => HttpResponse::Ok().json(response),
-        Err(_) => HttpResponse::InternalServerError().json("Failed to process wager"),
+        Ok(response) => {
+            metrics::record_wager_result(Some(&response.status));
+            rejection_response(&response).unwrap_or_else(|| HttpResponse::Ok().json(response))
+        }
+        Err(RpcError::ShuttingDown) => {
+            metrics::record_wager_result(None);
+            HttpResponse::ServiceUnavailable().json("Service is shutting down")
+        }
+        Err(_) => {
+            metrics::record_wager_result(None);
+            HttpResponse::InternalServerError().json("Failed to process wager")
+        }
+    }
+}
+
+/// Maps a wager the engine refused to play, e.g. because its pool is paused, to an error
+/// response; `None` when the wager was played.
+fn rejection_response(response: &WagerResponse) -> Option<HttpResponse> {
+    match response.error.as_deref()? {
+        "pool_paused" => Some(HttpResponse::ServiceUnavailable().json(response)),
+        // The engine gave up after retrying and parked the wager for inspection.
+        "processing_failed" => Some(HttpResponse::InternalServerError().json(response)),
+        _ => Some(HttpResponse::UnprocessableEntity().json(response)),
+    }
+}
+
+async fn submit_async(
+    rpc_client: web::Data<RpcClient<WagerResponse>>,
+    status_store: web::Data<dyn WagerStatusStore>,
+    callback_client: web::Data<CallbackClient>,
+    request: WagerRequest,
+    callback_secret: Option<SecretString>,
+) -> HttpResponse {
+    let wager_id = request.id.expect("wager id is assigned before submission");
+    let pending = WagerStatus {
+        wager_id,
+        site_id: request.site_id,
+        state: WagerState::Pending,
+        updated_at: Utc::now(),
+    };
+
+    // A caller-chosen id must not overwrite, or expose, another submission's status.
+    match status_store.create(&pending).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::Conflict().json("A wager with this id was already submitted");
+        }
+        Err(e) => {
+            tracing::error!(wager_id = %wager_id, "Failed to record pending wager: {:?}", e);
+            return HttpResponse::InternalServerError().json("Failed to process wager");
+        }
+    }
+
+    actix_web::rt::spawn(async move {
+        let state = match rpc_client.call(&request).await {
+            Ok(result) => {
+                metrics::record_wager_result(Some(&result.status));
+                WagerState::Completed { result }
+            }
+            Err(e) => {
+                metrics::record_wager_result(None);
+                tracing::error!(wager_id = %wager_id, "Asynchronous wager failed: {:?}", e);
+                WagerState::Failed {
+                    error: "Failed to process wager".to_string(),
+                }
+            }
+        };
+        let status = WagerStatus {
+            wager_id,
+            site_id: request.site_id,
+            state,
+            updated_at: Utc::now(),
+        };
+
+        if let Err(e) = status_store.put(&status).await {
+            tracing::error!(wager_id = %wager_id, "Failed to record wager result: {:?}", e);
+        }
+        let (Some(callback_url), Some(secret)) = (&request.callback_url, &callback_secret) else {
+            return;
+        };
+        if let Err(e) = callback_client.notify(callback_url, secret, &status).await {
+            tracing::warn!(wager_id = %wager_id, "Failed to deliver wager callback: {:?}", e);
+        }
+    });
+
+    HttpResponse::Accepted()
+        .insert_header((
+            header::LOCATION,
+            format!("/api/v1/wager/{}/status", wager_id),
+        ))
+        .json(pending)
+}
+
+// GET /{id}/status - Reports the progress of an asynchronously submitted wager
+pub async fn get_wager_status(
+    status_store: web::Data<dyn WagerStatusStore>,
+    api_key: web::ReqData<ApiKey>,
+    id: web::Path<Uuid>,
+) -> HttpResponse {
+    let id = id.into_inner();
+
+    match status_store.get(id).await {
+        Ok(Some(status)) if api_key.allows_site(status.site_id) => HttpResponse::Ok().json(status),
+        Ok(_) => HttpResponse::NotFound().json("Wager status not found"),
+        Err(e) => {
+            tracing::error!(wager_id = %id, "Failed to load wager status: {:?}", e);
+            HttpResponse::InternalServerError().json("Failed to load wager status")
+        }
+    }
+}
+
+// GET /{id} - Looks up a single wager in storage
+pub async fn get_wager(
+    storage_client: web::Data<StorageClient>,
+    api_key: web::ReqData<ApiKey>,
+    id: web::Path<Uuid>,
+) -> HttpResponse {
+    let id = id.into_inner();
+
+    match storage_client.get_wager(id).await {
+        // Wagers of other sites are reported as missing rather than forbidden.
+        Ok(Some(wager)) if api_key.allows_site(wager.site_id) => HttpResponse::Ok().json(wager),
+        Ok(_) => HttpResponse::NotFound().json("Wager not found"),
+        Err(e) => {
+            tracing::error!(wager_id = %id, "Failed to load wager: {:?}", e);
+            HttpResponse::InternalServerError().json("Failed to load wager")
+        }
+    }
+}
+
+// GET / - Lists a user's wagers with pagination and date filters
+pub async fn list_wagers(
+    storage_client: web::Data<StorageClient>,
+    api_key: web::ReqData<ApiKey>,
+    query: web::Query<WagerListQuery>,
+) -> HttpResponse {
+    let query = query.into_inner();
+
+    if !api_key.allows_site(query.site_id) {
+        return HttpResponse::Forbidden().json("API key is not authorized for this site");
+    }
+
+    match storage_client.list_wagers(&query).await {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(e) => {
+            tracing::error!(
+                site_id = query.site_id,
+                user_id = query.user_id,
+                "Failed to list wagers: {:?}",
+                e
+            );
+            HttpResponse::InternalServerError().json("Failed to list wagers")
+        }
     }
 }
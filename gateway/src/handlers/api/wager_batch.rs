@@ -0,0 +1,159 @@
+use std::collections::HashSet;
+
+use crate::{
+    configuration::BatchConfig,
+    domain::models::{
+        ApiKey, BatchItemError, BatchItemResult, WagerBatchRequest, WagerBatchResponse,
+        WagerRequest, WagerResponse,
+    },
+    handlers::maintenance::under_maintenance,
+    maintenance::Maintenance,
+    messaging::rpc_client::RpcClient,
+    metrics::{self, BATCH_SIZE, WAGERS_SUBMITTED},
+    rate_limit::RateLimiter,
+};
+use actix_web::{HttpResponse, http::header, web};
+use chrono::Utc;
+use futures::{StreamExt, stream};
+use serde_json::json;
+use uuid::Uuid;
+
+// POST / - Validates a batch of wagers, then sends them to RabbitMQ with bounded concurrency
+pub async fn create_wager_batch(
+    rpc_client: web::Data<RpcClient<WagerResponse>>,
+    rate_limiter: web::Data<RateLimiter>,
+    batch_config: web::Data<BatchConfig>,
+    maintenance: web::Data<Maintenance>,
+    api_key: web::ReqData<ApiKey>,
+    request: web::Json<WagerBatchRequest>,
+) -> HttpResponse {
+    let received_at = Utc::now();
+    let mut wagers = request.into_inner().wagers;
+
+    if wagers.is_empty() {
+        return HttpResponse::BadRequest().json("Batch must contain at least one wager");
+    }
+    if wagers.len() > batch_config.max_items {
+        return HttpResponse::PayloadTooLarge().json(format!(
+            "Batch must not contain more than {} wagers",
+            batch_config.max_items
+        ));
+    }
+
+    // Nothing is published unless every wager in the batch is valid.
+    let errors = validate_batch(&wagers, &api_key);
+    if !errors.is_empty() {
+        tracing::warn!(
+            rejected = errors.len(),
+            batch_size = wagers.len(),
+            "Wager batch failed validation"
+        );
+        BATCH_SIZE
+            .with_label_values(&["rejected"])
+            .observe(wagers.len() as f64);
+        return HttpResponse::UnprocessableEntity().json(json!({ "errors": errors }));
+    }
+
+    let site_ids: HashSet<i32> = wagers.iter().map(|wager| wager.site_id).collect();
+    if let Err(retry_after) = maintenance.check(site_ids).await {
+        tracing::warn!("Wager batch rejected during maintenance");
+        BATCH_SIZE
+            .with_label_values(&["rejected"])
+            .observe(wagers.len() as f64);
+        return under_maintenance(retry_after);
+    }
+
+    for wager in &mut wagers {
+        wager.id.get_or_insert_with(Uuid::new_v4);
+        wager.received_at = Some(received_at);
+    }
+
+    // Each wager takes its own tokens; refused wagers are reported in place and not
+    // published, and take nothing from the buckets.
+    let mut refusals = Vec::with_capacity(wagers.len());
+    for wager in &wagers {
+        refusals.push(rate_limiter.check(wager.site_id, wager.user_id).await.err());
+    }
+    let refused = refusals.iter().filter(|refusal| refusal.is_some()).count();
+    if refused == wagers.len() {
+        tracing::warn!(
+            batch_size = wagers.len(),
+            "Wager batch rejected by rate limiter"
+        );
+        BATCH_SIZE
+            .with_label_values(&["rejected"])
+            .observe(wagers.len() as f64);
+        let retry_after = refusals.into_iter().flatten().min().unwrap_or_default();
+        return HttpResponse::TooManyRequests()
+            .insert_header((header::RETRY_AFTER, retry_after.as_secs_f64().ceil() as u64))
+            .json("Rate limit exceeded");
+    }
+    if refused > 0 {
+        tracing::warn!(
+            refused,
+            batch_size = wagers.len(),
+            "Wagers of batch rejected by rate limiter"
+        );
+    }
+
+    BATCH_SIZE
+        .with_label_values(&["accepted"])
+        .observe(wagers.len() as f64);
+    WAGERS_SUBMITTED
+        .with_label_values(&["batch"])
+        .inc_by((wagers.len() - refused) as u64);
+
+    let results = stream::iter(wagers.into_iter().zip(refusals))
+        .map(|(wager, refusal)| {
+            let rpc_client = rpc_client.clone();
+            async move {
+                let wager_id = wager.id.expect("wager id is assigned before submission");
+                if let Some(retry_after) = refusal {
+                    return BatchItemResult::RateLimited {
+                        wager_id,
+                        retry_after_secs: retry_after.as_secs_f64().ceil() as u64,
+                    };
+                }
+                match rpc_client.call(&wager).await {
+                    Ok(result) => {
+                        metrics::record_wager_result(Some(&result.status));
+                        BatchItemResult::Ok { wager_id, result }
+                    }
+                    Err(e) => {
+                        metrics::record_wager_result(None);
+                        tracing::error!(wager_id = %wager_id, "Batch wager failed: {:?}", e);
+                        BatchItemResult::Error {
+                            wager_id,
+                            error: "Failed to process wager".to_string(),
+                        }
+                    }
+                }
+            }
+        })
+        .buffered(batch_config.max_concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+
+    HttpResponse::Ok().json(WagerBatchResponse { results })
+}
+
+fn validate_batch(wagers: &[WagerRequest], api_key: &ApiKey) -> Vec<BatchItemError> {
+    let mut seen_ids = HashSet::new();
+
+    wagers
+        .iter()
+        .enumerate()
+        .filter_map(|(index, wager)| {
+            let error = if !api_key.allows_site(wager.site_id) {
+                Some("API key is not authorized for this site".to_string())
+            } else if let Err(e) = wager.validate() {
+                Some(e)
+            } else if wager.id.is_some_and(|id| !seen_ids.insert(id)) {
+                Some("duplicate wager id in batch".to_string())
+            } else {
+                None
+            };
+            error.map(|error| BatchItemError { index, error })
+        })
+        .collect()
+}
@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use actix_web::{HttpResponse, http::header, web};
+
+use crate::maintenance::{Maintenance, MaintenanceState};
+
+// GET /admin/maintenance - Reports which sites are under maintenance
+pub async fn get_maintenance(maintenance: web::Data<Maintenance>) -> HttpResponse {
+    match maintenance.state().await {
+        Ok(state) => HttpResponse::Ok().json(state),
+        Err(e) => {
+            tracing::error!("Failed to load maintenance state: {:?}", e);
+            HttpResponse::InternalServerError().json("Failed to load maintenance state")
+        }
+    }
+}
+
+// PUT /admin/maintenance - Takes every site, or the listed ones, offline or back online
+pub async fn set_maintenance(
+    maintenance: web::Data<Maintenance>,
+    state: web::Json<MaintenanceState>,
+) -> HttpResponse {
+    let state = state.into_inner();
+    match maintenance.set(&state).await {
+        Ok(()) => {
+            tracing::warn!(
+                enabled = state.enabled,
+                site_ids = ?state.site_ids,
+                "Maintenance state changed"
+            );
+            HttpResponse::Ok().json(state)
+        }
+        Err(e) => {
+            tracing::error!("Failed to store maintenance state: {:?}", e);
+            HttpResponse::InternalServerError().json("Failed to store maintenance state")
+        }
+    }
+}
+
+/// Response for wagers turned away because their site is under maintenance.
+pub fn under_maintenance(retry_after: Duration) -> HttpResponse {
+    HttpResponse::ServiceUnavailable()
+        .insert_header((header::RETRY_AFTER, retry_after.as_secs()))
+        .json("Site is under maintenance")
+}
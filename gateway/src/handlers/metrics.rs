@@ -0,0 +1,15 @@
+use actix_web::{HttpResponse, Responder};
+
+use crate::metrics;
+
+pub async fn metrics() -> impl Responder {
+    match metrics::render() {
+        Ok(body) => HttpResponse::Ok()
+            .content_type("text/plain; version=0.0.4")
+            .body(body),
+        Err(e) => {
+            tracing::error!("Failed to render metrics: {:?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
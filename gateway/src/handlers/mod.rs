@@ -1 +1,3 @@
 pub mod api;
+pub mod maintenance;
+pub mod metrics;
@@ -1,7 +1,14 @@
 pub mod application;
+pub mod auth;
+pub mod clients;
 pub mod configuration;
 pub mod domain;
 pub mod handlers;
+pub mod maintenance;
 pub mod messaging;
+pub mod metrics;
+pub mod middleware;
+pub mod rate_limit;
 pub mod routes;
 pub mod telemetry;
+pub mod wager_status;
@@ -1,17 +1,22 @@
 use gateway::{
     application::Application,
     configuration::get_configuration,
-    telemetry::{get_subscriber, init_subscriber},
+    telemetry::{get_subscriber, init_subscriber, shutdown_tracer_provider},
 };
 use std::fmt::{Debug, Display};
 use tokio::task::JoinError;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let subscriber = get_subscriber("gateway".into(), "info".into(), std::io::stdout);
-    init_subscriber(subscriber);
-
     let configuration = get_configuration().expect("Failed to read configuration.");
+
+    let subscriber = get_subscriber(
+        "gateway".into(),
+        "info".into(),
+        std::io::stdout,
+        configuration.telemetry.otlp_endpoint.as_deref(),
+    );
+    init_subscriber(subscriber);
     let application = Application::build(configuration.clone()).await?;
     let application_task = tokio::spawn(application.run_until_stopped());
 
@@ -19,6 +24,7 @@ async fn main() -> anyhow::Result<()> {
         o = application_task => report_exit("API", o),
     }
 
+    shutdown_tracer_provider();
     Ok(())
 }
 
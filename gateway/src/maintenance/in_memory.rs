@@ -0,0 +1,33 @@
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+
+use super::{MaintenanceState, MaintenanceStore};
+
+/// Keeps the maintenance state in process memory; changes apply to this gateway replica only.
+#[derive(Default)]
+pub struct InMemoryMaintenanceStore {
+    state: RwLock<Option<MaintenanceState>>,
+}
+
+impl InMemoryMaintenanceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl MaintenanceStore for InMemoryMaintenanceStore {
+    async fn get(&self) -> anyhow::Result<Option<MaintenanceState>> {
+        Ok(self
+            .state
+            .read()
+            .expect("maintenance state poisoned")
+            .clone())
+    }
+
+    async fn set(&self, state: &MaintenanceState) -> anyhow::Result<()> {
+        *self.state.write().expect("maintenance state poisoned") = Some(state.clone());
+        Ok(())
+    }
+}
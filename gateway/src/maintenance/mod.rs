@@ -0,0 +1,79 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::configuration::MaintenanceConfig;
+
+pub mod in_memory;
+pub mod redis_store;
+
+/// Sites that are currently not taking wagers.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct MaintenanceState {
+    /// Takes every site offline.
+    pub enabled: bool,
+    #[serde(default)]
+    pub site_ids: Vec<i32>,
+}
+
+impl MaintenanceState {
+    pub fn covers(&self, site_id: i32) -> bool {
+        self.enabled || self.site_ids.contains(&site_id)
+    }
+}
+
+#[async_trait]
+pub trait MaintenanceStore: Send + Sync {
+    /// Returns `None` until a state has been set.
+    async fn get(&self) -> anyhow::Result<Option<MaintenanceState>>;
+    async fn set(&self, state: &MaintenanceState) -> anyhow::Result<()>;
+}
+
+/// Site-wide maintenance switch, checked before a wager is sent to the engine.
+pub struct Maintenance {
+    store: Arc<dyn MaintenanceStore>,
+    configured: MaintenanceState,
+    retry_after: Duration,
+}
+
+impl Maintenance {
+    pub fn new(store: Arc<dyn MaintenanceStore>, config: &MaintenanceConfig) -> Self {
+        Self {
+            store,
+            configured: MaintenanceState {
+                enabled: config.enabled,
+                site_ids: config.site_ids.clone(),
+            },
+            retry_after: Duration::from_secs(config.retry_after_secs),
+        }
+    }
+
+    /// The state last set through the admin API, or the configured one if none was.
+    pub async fn state(&self) -> anyhow::Result<MaintenanceState> {
+        Ok(self
+            .store
+            .get()
+            .await?
+            .unwrap_or_else(|| self.configured.clone()))
+    }
+
+    pub async fn set(&self, state: &MaintenanceState) -> anyhow::Result<()> {
+        self.store.set(state).await
+    }
+
+    /// Returns the `Retry-After` delay when any of `site_ids` is under maintenance. Store
+    /// failures fall back to the configured state.
+    pub async fn check(&self, site_ids: impl IntoIterator<Item = i32>) -> Result<(), Duration> {
+        let state = self.state().await.unwrap_or_else(|e| {
+            error!("Maintenance check failed: {:?}", e);
+            self.configured.clone()
+        });
+        if site_ids.into_iter().any(|site_id| state.covers(site_id)) {
+            Err(self.retry_after)
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+use redis::{AsyncCommands, aio::ConnectionManager};
+
+use super::{MaintenanceState, MaintenanceStore};
+
+const KEY: &str = "gateway:maintenance";
+
+/// Keeps the maintenance state in Redis so one switch covers every gateway replica.
+pub struct RedisMaintenanceStore {
+    redis: ConnectionManager,
+}
+
+impl RedisMaintenanceStore {
+    pub async fn new(redis_url: &str) -> anyhow::Result<Self> {
+        let client = redis::Client::open(redis_url)?;
+        let redis = ConnectionManager::new(client).await?;
+        Ok(Self { redis })
+    }
+}
+
+#[async_trait]
+impl MaintenanceStore for RedisMaintenanceStore {
+    async fn get(&self) -> anyhow::Result<Option<MaintenanceState>> {
+        let payload: Option<String> = self.redis.clone().get(KEY).await?;
+        payload
+            .map(|payload| serde_json::from_str(&payload))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn set(&self, state: &MaintenanceState) -> anyhow::Result<()> {
+        let payload = serde_json::to_string(state)?;
+        let _: () = self.redis.clone().set(KEY, payload).await?;
+        Ok(())
+    }
+}
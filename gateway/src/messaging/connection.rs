@@ -23,4 +23,11 @@ impl RabbitConnection {
             .await
             .context("Failed to create RabbitMQ channel")
     }
+
+    /// Closes the connection and every channel opened on it.
+    pub async fn close(&self) {
+        if let Err(e) = self.connection.close(200, "shutting down").await {
+            tracing::warn!("Failed to close RabbitMQ connection: {:?}", e);
+        }
+    }
 }
@@ -1,2 +1,3 @@
 pub mod connection;
 pub mod rpc_client;
+pub mod trace_context;
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::{Context, anyhow};
 use futures::StreamExt;
@@ -9,18 +13,35 @@ use lapin::{
 };
 use serde::de::DeserializeOwned;
 use tokio::sync::{Mutex, oneshot};
+use tokio_util::sync::CancellationToken;
 use tracing::{error, instrument};
 use uuid::Uuid;
 
-use crate::domain::models::WagerRequest;
+use crate::{
+    domain::models::WagerRequest,
+    metrics::{RPC_DURATION, RPC_PENDING_REQUESTS},
+};
+
+use super::{connection::RabbitConnection, trace_context};
 
-use super::connection::RabbitConnection;
+const RPC_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, thiserror::Error)]
+pub enum RpcError {
+    #[error("RPC client is shutting down")]
+    ShuttingDown,
+    #[error("RPC timeout")]
+    Timeout,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
 
 pub struct RpcClient<Response> {
     channel: Arc<Channel>,
     exchange_name: String,
     reply_queue_name: String,
     pending_requests: Arc<Mutex<HashMap<String, oneshot::Sender<Response>>>>,
+    shutdown: CancellationToken,
 }
 
 impl<Response> RpcClient<Response>
@@ -66,6 +87,7 @@ where
             exchange_name: exchange_name.to_string(),
             reply_queue_name,
             pending_requests,
+            shutdown: CancellationToken::new(),
         };
 
         client.spawn_reply_consumer();
@@ -74,16 +96,53 @@ where
     }
 
     #[instrument(name = "rpc.send_request", skip(self, request))]
-    pub async fn call(&self, request: &WagerRequest) -> anyhow::Result<Response> {
+    pub async fn call(&self, request: &WagerRequest) -> Result<Response, RpcError> {
+        if self.shutdown.is_cancelled() {
+            return Err(RpcError::ShuttingDown);
+        }
+
         let correlation_id = Uuid::new_v4().to_string();
         let (tx, rx) = oneshot::channel();
+        let started_at = Instant::now();
 
         // Proper async lock handling
-        self.pending_requests
-            .lock()
-            .await
-            .insert(correlation_id.clone(), tx);
+        {
+            let mut pending_requests = self.pending_requests.lock().await;
+            pending_requests.insert(correlation_id.clone(), tx);
+            RPC_PENDING_REQUESTS.set(pending_requests.len() as i64);
+        }
 
+        let (outcome, result) = match self.publish(&correlation_id, request).await {
+            Ok(()) => tokio::select! {
+                reply = tokio::time::timeout(RPC_TIMEOUT, rx) => match reply {
+                    Ok(Ok(res)) => ("ok", Ok(res)),
+                    Ok(Err(_)) => ("error", Err(anyhow!("Response channel closed").into())),
+                    Err(_) => ("timeout", Err(RpcError::Timeout)),
+                },
+                () = self.shutdown.cancelled() => ("shutdown", Err(RpcError::ShuttingDown)),
+            },
+            Err(e) => ("error", Err(e.into())),
+        };
+
+        if result.is_err() {
+            // No reply is coming for this request; don't leave its sender behind.
+            let mut pending_requests = self.pending_requests.lock().await;
+            pending_requests.remove(&correlation_id);
+            RPC_PENDING_REQUESTS.set(pending_requests.len() as i64);
+        }
+        RPC_DURATION
+            .with_label_values(&[outcome])
+            .observe(started_at.elapsed().as_secs_f64());
+
+        result
+    }
+
+    /// Fails every pending and future call with [`RpcError::ShuttingDown`].
+    pub fn shutdown(&self) {
+        self.shutdown.cancel();
+    }
+
+    async fn publish(&self, correlation_id: &str, request: &WagerRequest) -> anyhow::Result<()> {
         let payload = serde_json::to_vec(&request)?;
         self.channel
             .basic_publish(
@@ -92,17 +151,13 @@ where
                 BasicPublishOptions::default(),
                 &payload,
                 BasicProperties::default()
-                    .with_correlation_id(ShortString::from(correlation_id.clone()))
-                    .with_reply_to(ShortString::from(self.reply_queue_name.clone())),
+                    .with_correlation_id(ShortString::from(correlation_id))
+                    .with_reply_to(ShortString::from(self.reply_queue_name.clone()))
+                    .with_headers(trace_context::current_headers()),
             )
             .await?
             .await?;
-
-        match tokio::time::timeout(Duration::from_secs(5), rx).await {
-            Ok(Ok(res)) => Ok(res),
-            Ok(Err(_)) => Err(anyhow!("Response channel closed")),
-            Err(_) => Err(anyhow!("RPC timeout")),
-        }
+        Ok(())
     }
 
     fn spawn_reply_consumer(&self) {
@@ -140,7 +195,13 @@ where
                             let corr_str = corr_id.as_str().to_string();
 
                             // Handle response processing
-                            let tx = match pending_requests.lock().await.remove(&corr_str) {
+                            let tx = {
+                                let mut pending_requests = pending_requests.lock().await;
+                                let tx = pending_requests.remove(&corr_str);
+                                RPC_PENDING_REQUESTS.set(pending_requests.len() as i64);
+                                tx
+                            };
+                            let tx = match tx {
                                 Some(tx) => tx,
                                 None => {
                                     error!("No pending request for correlation ID: {}", corr_str);
@@ -150,7 +211,7 @@ where
 
                             match serde_json::from_slice::<Response>(&delivery.data) {
                                 Ok(response) => {
-                                    if let Err(_) = tx.send(response) {
+                                    if tx.send(response).is_err() {
                                         error!("Failed to send response");
                                     }
                                 }
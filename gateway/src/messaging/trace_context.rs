@@ -0,0 +1,27 @@
+//! W3C trace context (`traceparent`/`tracestate`) carried in AMQP message headers.
+
+use lapin::types::{AMQPValue, FieldTable, LongString, ShortString};
+use opentelemetry::{global, propagation::Injector};
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+struct HeaderInjector<'a>(&'a mut FieldTable);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        self.0.insert(
+            ShortString::from(key),
+            AMQPValue::LongString(LongString::from(value)),
+        );
+    }
+}
+
+/// Headers carrying the current span's trace context, to attach to a publish.
+pub fn current_headers() -> FieldTable {
+    let mut headers = FieldTable::default();
+    let context = Span::current().context();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(&mut headers))
+    });
+    headers
+}
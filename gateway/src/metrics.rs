@@ -0,0 +1,70 @@
+use std::sync::LazyLock;
+
+use prometheus::{
+    Encoder, HistogramVec, IntCounterVec, IntGauge, TextEncoder, exponential_buckets,
+    register_histogram_vec, register_int_counter_vec, register_int_gauge,
+};
+
+pub static WAGERS_SUBMITTED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "gateway_wagers_submitted_total",
+        "Wagers accepted for processing, by submission mode",
+        &["mode"]
+    )
+    .expect("metric can be registered")
+});
+
+pub static WAGER_RESULTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "gateway_wager_results_total",
+        "Engine results for submitted wagers (won, lost, rejected or error)",
+        &["outcome"]
+    )
+    .expect("metric can be registered")
+});
+
+pub static RPC_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "gateway_rpc_duration_seconds",
+        "Round-trip time of wager RPCs to the engine",
+        &["outcome"],
+        exponential_buckets(0.001, 2.0, 14).expect("valid buckets")
+    )
+    .expect("metric can be registered")
+});
+
+pub static RPC_PENDING_REQUESTS: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!(
+        "gateway_rpc_pending_requests",
+        "RPCs waiting for an engine reply"
+    )
+    .expect("metric can be registered")
+});
+
+pub static BATCH_SIZE: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "gateway_batch_size",
+        "Wagers per batch submission",
+        &["outcome"],
+        exponential_buckets(1.0, 2.0, 11).expect("valid buckets")
+    )
+    .expect("metric can be registered")
+});
+
+/// Records the engine's answer to a wager; the engine reports wins as status `"true"`.
+pub fn record_wager_result(status: Option<&str>) {
+    let outcome = match status {
+        Some("true") => "won",
+        Some("rejected") => "rejected",
+        Some(_) => "lost",
+        None => "error",
+    };
+    WAGER_RESULTS.with_label_values(&[outcome]).inc();
+}
+
+/// Renders every registered metric in the Prometheus text format.
+pub fn render() -> anyhow::Result<String> {
+    let mut buffer = Vec::new();
+    TextEncoder::new().encode(&prometheus::gather(), &mut buffer)?;
+    Ok(String::from_utf8(buffer)?)
+}
@@ -0,0 +1,71 @@
+use actix_web::{
+    Error, HttpMessage, HttpResponse,
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+    web::Data,
+};
+use chrono::Utc;
+use tracing::{error, warn};
+
+use crate::auth::{ApiKeyStore, hash_api_key};
+
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// Authenticates the request using the `X-Api-Key` header.
+///
+/// Responds with 401 when the key is missing, unknown or outside its validity window.
+/// On success the matching [`ApiKey`](crate::domain::models::ApiKey) is stored in the
+/// request extensions so handlers can enforce per-site authorization.
+pub async fn require_api_key(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let Some(raw_key) = req
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return Ok(reject(
+            req,
+            HttpResponse::Unauthorized().json("Missing API key"),
+        ));
+    };
+
+    let store = req
+        .app_data::<Data<dyn ApiKeyStore>>()
+        .expect("ApiKeyStore is not registered as app data")
+        .clone();
+
+    let candidates = match store.find_by_hash(&hash_api_key(raw_key)).await {
+        Ok(keys) => keys,
+        Err(e) => {
+            error!("Failed to look up API key: {:?}", e);
+            return Ok(reject(
+                req,
+                HttpResponse::InternalServerError().json("Failed to authenticate request"),
+            ));
+        }
+    };
+
+    let now = Utc::now();
+    match candidates.into_iter().find(|key| key.is_active(now)) {
+        Some(api_key) => {
+            req.extensions_mut().insert(api_key);
+            next.call(req)
+                .await
+                .map(ServiceResponse::map_into_left_body)
+        }
+        None => {
+            warn!("Rejected request with unknown or expired API key");
+            Ok(reject(
+                req,
+                HttpResponse::Unauthorized().json("Invalid API key"),
+            ))
+        }
+    }
+}
+
+fn reject<B>(req: ServiceRequest, response: HttpResponse) -> ServiceResponse<EitherBody<B>> {
+    req.into_response(response).map_into_right_body()
+}
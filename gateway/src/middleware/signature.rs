@@ -0,0 +1,97 @@
+use actix_web::{
+    Error, HttpMessage, HttpResponse,
+    body::{EitherBody, MessageBody},
+    dev::{Payload, ServiceRequest, ServiceResponse},
+    middleware::Next,
+    web::{Bytes, Data},
+};
+use chrono::Utc;
+use tracing::warn;
+
+use crate::{
+    auth::signature::{SignatureError, SignatureVerifier, SignedRequest},
+    domain::models::ApiKey,
+};
+
+pub const OPERATOR_ID_HEADER: &str = "X-Operator-Id";
+pub const TIMESTAMP_HEADER: &str = "X-Timestamp";
+pub const NONCE_HEADER: &str = "X-Nonce";
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+
+/// Verifies the operator's HMAC-SHA256 signature over method, path, timestamp, nonce and body.
+///
+/// Runs after [`require_api_key`](super::api_key::require_api_key): the signing operator
+/// must be the one the API key was issued to. Responds with 401 when signing is enabled and
+/// the signature is missing, invalid, outside the timestamp window, reuses a nonce or
+/// belongs to another operator.
+pub async fn require_signature(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let verifier = req
+        .app_data::<Data<SignatureVerifier>>()
+        .expect("SignatureVerifier is not registered as app data")
+        .clone();
+
+    if !verifier.is_enabled() {
+        return next
+            .call(req)
+            .await
+            .map(ServiceResponse::map_into_left_body);
+    }
+
+    // The body has to be buffered to be signed; put it back for the handler afterwards.
+    let body = req.extract::<Bytes>().await?;
+    req.set_payload(Payload::from(body.clone()));
+
+    let result = verify_request(&req, &body, &verifier).await;
+
+    match result {
+        Ok(()) => next
+            .call(req)
+            .await
+            .map(ServiceResponse::map_into_left_body),
+        Err(SignatureError::NonceStoreUnavailable) => Ok(req
+            .into_response(HttpResponse::InternalServerError().json("Failed to verify signature"))
+            .map_into_right_body()),
+        Err(e) => {
+            warn!(error = %e, "Rejected request with invalid signature");
+            Ok(req
+                .into_response(HttpResponse::Unauthorized().json(e.to_string()))
+                .map_into_right_body())
+        }
+    }
+}
+
+async fn verify_request(
+    req: &ServiceRequest,
+    body: &[u8],
+    verifier: &SignatureVerifier,
+) -> Result<(), SignatureError> {
+    let api_key_id = req
+        .extensions()
+        .get::<ApiKey>()
+        .map(|api_key| api_key.id.clone())
+        .ok_or(SignatureError::OperatorMismatch)?;
+    let header = |name: &'static str| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .ok_or(SignatureError::MissingHeader(name))
+    };
+
+    let signed = SignedRequest {
+        api_key_id: &api_key_id,
+        operator_id: header(OPERATOR_ID_HEADER)?,
+        timestamp: header(TIMESTAMP_HEADER)?,
+        nonce: header(NONCE_HEADER)?,
+        signature: header(SIGNATURE_HEADER)?,
+        method: req.method().as_str(),
+        path: req
+            .uri()
+            .path_and_query()
+            .map_or_else(|| req.path(), |pq| pq.as_str()),
+        body,
+    };
+    verifier.verify(&signed, Utc::now().timestamp()).await
+}
@@ -0,0 +1,92 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+
+use super::BucketStore;
+use crate::configuration::BucketPolicy;
+
+/// How often buckets that have refilled completely are forgotten.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+    // When the bucket is back at capacity; it is then no different from a missing one.
+    full_at: Instant,
+}
+
+struct Buckets {
+    buckets: HashMap<String, Bucket>,
+    next_sweep: Instant,
+}
+
+/// Keeps token buckets in process memory; limits apply per gateway replica.
+pub struct InMemoryBucketStore {
+    state: Mutex<Buckets>,
+}
+
+impl InMemoryBucketStore {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(Buckets {
+                buckets: HashMap::new(),
+                next_sweep: Instant::now() + SWEEP_INTERVAL,
+            }),
+        }
+    }
+}
+
+impl Default for InMemoryBucketStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl BucketStore for InMemoryBucketStore {
+    async fn take(&self, buckets: &[(String, BucketPolicy)]) -> anyhow::Result<Option<Duration>> {
+        let now = Instant::now();
+        let mut guard = self.state.lock().expect("rate limit buckets poisoned");
+        if now >= guard.next_sweep {
+            guard.buckets.retain(|_, bucket| bucket.full_at > now);
+            guard.next_sweep = now + SWEEP_INTERVAL;
+        }
+        let state = &mut guard.buckets;
+
+        // Refill every bucket first and only debit once all of them have a token.
+        let mut wait: f64 = 0.0;
+        for (key, policy) in buckets {
+            let bucket = state.entry(key.clone()).or_insert(Bucket {
+                tokens: policy.capacity,
+                updated_at: now,
+                full_at: now,
+            });
+
+            let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * policy.refill_per_sec).min(policy.capacity);
+            bucket.updated_at = now;
+
+            if bucket.tokens < 1.0 {
+                wait = wait.max((1.0 - bucket.tokens) / policy.refill_per_sec);
+            }
+        }
+        if wait > 0.0 {
+            return Ok(Some(Duration::from_secs_f64(wait)));
+        }
+
+        for (key, policy) in buckets {
+            if let Some(bucket) = state.get_mut(key) {
+                bucket.tokens -= 1.0;
+                bucket.full_at = now
+                    + Duration::from_secs_f64(
+                        (policy.capacity - bucket.tokens) / policy.refill_per_sec,
+                    );
+            }
+        }
+        Ok(None)
+    }
+}
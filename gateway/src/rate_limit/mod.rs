@@ -0,0 +1,57 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use tracing::error;
+
+use crate::configuration::{BucketPolicy, RateLimitConfig};
+
+pub mod in_memory;
+pub mod redis_store;
+
+#[async_trait]
+pub trait BucketStore: Send + Sync {
+    /// Takes one token from every bucket in `buckets`, or from none of them.
+    ///
+    /// Returns `None` when the request is allowed, or how long to wait until every bucket
+    /// has a token available.
+    async fn take(&self, buckets: &[(String, BucketPolicy)]) -> anyhow::Result<Option<Duration>>;
+}
+
+/// Per-site and per-user token-bucket rate limiting for wager submission.
+pub struct RateLimiter {
+    store: Arc<dyn BucketStore>,
+    site_policy: BucketPolicy,
+    user_policy: BucketPolicy,
+}
+
+impl RateLimiter {
+    pub fn new(store: Arc<dyn BucketStore>, config: &RateLimitConfig) -> Self {
+        Self {
+            store,
+            site_policy: config.site,
+            user_policy: config.user,
+        }
+    }
+
+    /// Takes a token from the site bucket and the user bucket together.
+    ///
+    /// Returns the `Retry-After` delay when either is exhausted, in which case neither is
+    /// debited, so one user's refused requests do not use up the site's allowance. Store
+    /// failures are logged and the request is let through, so a Redis outage does not take
+    /// wagering down.
+    pub async fn check(&self, site_id: i32, user_id: i32) -> Result<(), Duration> {
+        let buckets = [
+            (format!("site:{site_id}"), self.site_policy),
+            (format!("user:{site_id}:{user_id}"), self.user_policy),
+        ];
+
+        match self.store.take(&buckets).await {
+            Ok(None) => Ok(()),
+            Ok(Some(retry_after)) => Err(retry_after),
+            Err(e) => {
+                error!(site_id, user_id, "Rate limit check failed: {:?}", e);
+                Ok(())
+            }
+        }
+    }
+}
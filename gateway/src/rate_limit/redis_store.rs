@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use redis::{Script, aio::ConnectionManager};
+
+use super::BucketStore;
+use crate::configuration::BucketPolicy;
+
+// Refills every bucket in KEYS and takes a token from each only if all of them have one,
+// using Redis server time so every gateway replica sees the same buckets. ARGV holds a
+// (capacity, refill_per_sec) pair per key. Returns {allowed, retry_after_ms}.
+const TAKE_TOKEN_SCRIPT: &str = r#"
+local time = redis.call('TIME')
+local now_ms = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+
+local tokens = {}
+local retry_after_ms = 0
+for i, key in ipairs(KEYS) do
+    local capacity = tonumber(ARGV[2 * i - 1])
+    local refill_per_ms = tonumber(ARGV[2 * i]) / 1000
+    local state = redis.call('HMGET', key, 'tokens', 'updated_at')
+    local available = tonumber(state[1]) or capacity
+    local updated_at = tonumber(state[2]) or now_ms
+
+    available = math.min(capacity, available + (now_ms - updated_at) * refill_per_ms)
+    if available < 1 then
+        retry_after_ms = math.max(retry_after_ms, math.ceil((1 - available) / refill_per_ms))
+    end
+    tokens[i] = available
+end
+
+local allowed = 0
+if retry_after_ms == 0 then
+    allowed = 1
+end
+
+for i, key in ipairs(KEYS) do
+    local capacity = tonumber(ARGV[2 * i - 1])
+    local refill_per_ms = tonumber(ARGV[2 * i]) / 1000
+    local available = tokens[i] - allowed
+    redis.call('HSET', key, 'tokens', tostring(available), 'updated_at', now_ms)
+    redis.call('PEXPIRE', key, math.ceil(capacity / refill_per_ms) + 1000)
+end
+return {allowed, retry_after_ms}
+"#;
+
+/// Keeps token buckets in Redis so limits are shared by all gateway replicas.
+pub struct RedisBucketStore {
+    redis: ConnectionManager,
+    script: Script,
+}
+
+impl RedisBucketStore {
+    pub async fn new(redis_url: &str) -> anyhow::Result<Self> {
+        let client = redis::Client::open(redis_url)?;
+        let redis = ConnectionManager::new(client).await?;
+        Ok(Self {
+            redis,
+            script: Script::new(TAKE_TOKEN_SCRIPT),
+        })
+    }
+}
+
+#[async_trait]
+impl BucketStore for RedisBucketStore {
+    async fn take(&self, buckets: &[(String, BucketPolicy)]) -> anyhow::Result<Option<Duration>> {
+        let mut invocation = self.script.prepare_invoke();
+        for (key, policy) in buckets {
+            invocation
+                .key(format!("rate_limit:{key}"))
+                .arg(policy.capacity)
+                .arg(policy.refill_per_sec);
+        }
+        let (allowed, retry_after_ms): (i64, u64) =
+            invocation.invoke_async(&mut self.redis.clone()).await?;
+
+        if allowed == 1 {
+            Ok(None)
+        } else {
+            Ok(Some(Duration::from_millis(retry_after_ms)))
+        }
+    }
+}
@@ -1,9 +1,24 @@
-use actix_web::web;
+use actix_web::{middleware::from_fn, web};
+
+use crate::middleware::{api_key::require_api_key, signature::require_signature};
 
 pub mod health;
 pub mod wager;
+pub mod wager_batch;
 
 pub fn init(cfg: &mut web::ServiceConfig) {
-    cfg.service(web::scope("/wager").configure(wager::init));
+    cfg.service(
+        web::scope("/wager")
+            // The last `wrap` runs first: the key is authenticated before the signature.
+            .wrap(from_fn(require_signature))
+            .wrap(from_fn(require_api_key))
+            .configure(wager::init),
+    );
+    cfg.service(
+        web::scope("/wagers:batch")
+            .wrap(from_fn(require_signature))
+            .wrap(from_fn(require_api_key))
+            .configure(wager_batch::init),
+    );
     cfg.service(web::scope("/health").configure(health::init));
 }
@@ -1,6 +1,9 @@
-use crate::handlers::api::wager::create_wager;
+use crate::handlers::api::wager::{create_wager, get_wager, get_wager_status, list_wagers};
 use actix_web::web;
 
 pub fn init(cfg: &mut web::ServiceConfig) {
     cfg.route("", web::post().to(create_wager));
+    cfg.route("", web::get().to(list_wagers));
+    cfg.route("/{id}", web::get().to(get_wager));
+    cfg.route("/{id}/status", web::get().to(get_wager_status));
 }
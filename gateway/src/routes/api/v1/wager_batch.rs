@@ -0,0 +1,6 @@
+use crate::handlers::api::wager_batch::create_wager_batch;
+use actix_web::web;
+
+pub fn init(cfg: &mut web::ServiceConfig) {
+    cfg.route("", web::post().to(create_wager_batch));
+}
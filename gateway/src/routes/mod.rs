@@ -1,7 +1,19 @@
 use actix_web::web;
 
+use crate::handlers::{
+    maintenance::{get_maintenance, set_maintenance},
+    metrics::metrics,
+};
+
 pub mod api;
 
 pub fn init(cfg: &mut web::ServiceConfig) {
     cfg.service(web::scope("/api").configure(api::init));
 }
+
+/// Routes of the internal listener on `application.admin_port`.
+pub fn init_admin(cfg: &mut web::ServiceConfig) {
+    cfg.route("/metrics", web::get().to(metrics));
+    cfg.route("/admin/maintenance", web::get().to(get_maintenance));
+    cfg.route("/admin/maintenance", web::put().to(set_maintenance));
+}
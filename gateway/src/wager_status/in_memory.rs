@@ -0,0 +1,83 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use super::WagerStatusStore;
+use crate::domain::models::WagerStatus;
+
+/// How often expired statuses are forgotten.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Keeps wager statuses in process memory; only the replica that accepted the wager
+/// can answer status polls.
+pub struct InMemoryWagerStatusStore {
+    ttl: Duration,
+    state: Mutex<Statuses>,
+}
+
+struct Statuses {
+    // wager id -> (status, when it expires)
+    statuses: HashMap<Uuid, (WagerStatus, Instant)>,
+    next_sweep: Instant,
+}
+
+impl InMemoryWagerStatusStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            state: Mutex::new(Statuses {
+                statuses: HashMap::new(),
+                next_sweep: Instant::now() + SWEEP_INTERVAL,
+            }),
+        }
+    }
+
+    fn insert(&self, status: &WagerStatus, only_new: bool) -> bool {
+        let now = Instant::now();
+        let mut state = self.state.lock().expect("wager statuses poisoned");
+        if now >= state.next_sweep {
+            state
+                .statuses
+                .retain(|_, (_, expires_at)| *expires_at > now);
+            state.next_sweep = now + SWEEP_INTERVAL;
+        }
+
+        let exists = state
+            .statuses
+            .get(&status.wager_id)
+            .is_some_and(|(_, expires_at)| *expires_at > now);
+        if only_new && exists {
+            return false;
+        }
+        state
+            .statuses
+            .insert(status.wager_id, (status.clone(), now + self.ttl));
+        true
+    }
+}
+
+#[async_trait]
+impl WagerStatusStore for InMemoryWagerStatusStore {
+    async fn create(&self, status: &WagerStatus) -> anyhow::Result<bool> {
+        Ok(self.insert(status, true))
+    }
+
+    async fn put(&self, status: &WagerStatus) -> anyhow::Result<()> {
+        self.insert(status, false);
+        Ok(())
+    }
+
+    async fn get(&self, wager_id: Uuid) -> anyhow::Result<Option<WagerStatus>> {
+        let state = self.state.lock().expect("wager statuses poisoned");
+        Ok(state
+            .statuses
+            .get(&wager_id)
+            .filter(|(_, expires_at)| *expires_at > Instant::now())
+            .map(|(status, _)| status.clone()))
+    }
+}
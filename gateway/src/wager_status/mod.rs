@@ -0,0 +1,18 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::domain::models::WagerStatus;
+
+pub mod in_memory;
+pub mod redis_store;
+
+/// Keeps the status of asynchronously submitted wagers for polling.
+#[async_trait]
+pub trait WagerStatusStore: Send + Sync {
+    /// Records the first status of a wager; returns `false`, leaving the stored status
+    /// untouched, if a status is already kept for its id.
+    async fn create(&self, status: &WagerStatus) -> anyhow::Result<bool>;
+    /// Replaces the status of a wager recorded with [`create`](Self::create).
+    async fn put(&self, status: &WagerStatus) -> anyhow::Result<()>;
+    async fn get(&self, wager_id: Uuid) -> anyhow::Result<Option<WagerStatus>>;
+}
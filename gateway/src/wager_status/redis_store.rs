@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use redis::{AsyncCommands, ExistenceCheck, SetExpiry, SetOptions, aio::ConnectionManager};
+use uuid::Uuid;
+
+use super::WagerStatusStore;
+use crate::domain::models::WagerStatus;
+
+/// Keeps wager statuses in Redis so any gateway replica can answer status polls.
+pub struct RedisWagerStatusStore {
+    redis: ConnectionManager,
+    ttl: Duration,
+}
+
+impl RedisWagerStatusStore {
+    pub async fn new(redis_url: &str, ttl: Duration) -> anyhow::Result<Self> {
+        let client = redis::Client::open(redis_url)?;
+        let redis = ConnectionManager::new(client).await?;
+        Ok(Self { redis, ttl })
+    }
+
+    fn key(wager_id: Uuid) -> String {
+        format!("wager_status:{wager_id}")
+    }
+}
+
+#[async_trait]
+impl WagerStatusStore for RedisWagerStatusStore {
+    async fn create(&self, status: &WagerStatus) -> anyhow::Result<bool> {
+        let payload = serde_json::to_string(status)?;
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::NX)
+            .with_expiration(SetExpiry::EX(self.ttl.as_secs()));
+        let stored: Option<String> = self
+            .redis
+            .clone()
+            .set_options(Self::key(status.wager_id), payload, options)
+            .await?;
+        Ok(stored.is_some())
+    }
+
+    async fn put(&self, status: &WagerStatus) -> anyhow::Result<()> {
+        let payload = serde_json::to_string(status)?;
+        let _: () = self
+            .redis
+            .clone()
+            .set_ex(Self::key(status.wager_id), payload, self.ttl.as_secs())
+            .await?;
+        Ok(())
+    }
+
+    async fn get(&self, wager_id: Uuid) -> anyhow::Result<Option<WagerStatus>> {
+        let payload: Option<String> = self.redis.clone().get(Self::key(wager_id)).await?;
+        payload
+            .map(|payload| serde_json::from_str(&payload))
+            .transpose()
+            .map_err(Into::into)
+    }
+}
@@ -1 +1 @@
-DROP TABLE IF EXISTS jackpot.wagers;
+DROP TABLE IF EXISTS wagers;
@@ -1,6 +1,4 @@
-CREATE SCHEMA IF NOT EXISTS jackpot;
-
-CREATE TABLE IF NOT EXISTS jackpot.wagers (
+CREATE TABLE IF NOT EXISTS wagers (
     id UUID PRIMARY KEY,
     site_id INTEGER NOT NULL,
     game_id INTEGER NOT NULL,
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS api_keys (
+    id VARCHAR(64) NOT NULL,
+    key_hash CHAR(64) NOT NULL,
+    site_ids INTEGER[] NOT NULL,
+    not_before TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT NOW (),
+    PRIMARY KEY (id, key_hash)
+);
+
+CREATE INDEX IF NOT EXISTS api_keys_key_hash_idx ON api_keys (key_hash);
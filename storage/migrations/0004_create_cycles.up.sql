@@ -0,0 +1,19 @@
+-- One row per completed pool cycle, written when its winning wager is recorded.
+CREATE TABLE IF NOT EXISTS cycles (
+    pool_id TEXT NOT NULL,
+    cycle_id BIGINT NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL,
+    ended_at TIMESTAMPTZ NOT NULL,
+    seed BIGINT NOT NULL,
+    total_contributions BIGINT NOT NULL,
+    wager_count BIGINT NOT NULL,
+    winning_wager_id UUID NOT NULL,
+    winning_site_id INTEGER NOT NULL,
+    win_amount BIGINT NOT NULL,
+    -- RNG roll that triggered the win, for pools won by chance
+    trigger_value DOUBLE PRECISION,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW (),
+    PRIMARY KEY (pool_id, cycle_id)
+);
+
+CREATE INDEX IF NOT EXISTS cycles_ended_at_idx ON cycles (ended_at DESC);
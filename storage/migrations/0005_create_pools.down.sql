@@ -0,0 +1,7 @@
+ALTER TABLE wagers
+DROP COLUMN IF EXISTS pool_version,
+DROP COLUMN IF EXISTS pool_id;
+
+DROP TABLE IF EXISTS pool_versions;
+
+DROP TABLE IF EXISTS pools;
@@ -0,0 +1,35 @@
+-- Jackpot pools managed through the engine's admin API.
+CREATE TABLE IF NOT EXISTS pools (
+    id TEXT PRIMARY KEY,
+    -- active, paused or retired
+    status TEXT NOT NULL DEFAULT 'active',
+    current_version INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW (),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW ()
+);
+
+-- Every configuration a pool has had; rows are never updated so wagers stay auditable.
+CREATE TABLE IF NOT EXISTS pool_versions (
+    pool_id TEXT NOT NULL REFERENCES pools (id),
+    version INTEGER NOT NULL,
+    site_ids INTEGER[] NOT NULL,
+    seed BIGINT NOT NULL,
+    contribution_rate DOUBLE PRECISION NOT NULL,
+    hit_probability DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW (),
+    PRIMARY KEY (pool_id, version)
+);
+
+-- The pool previously defined in the engine's YAML configuration.
+INSERT INTO pools (id, status, current_version)
+VALUES ('main', 'active', 1)
+ON CONFLICT (id) DO NOTHING;
+
+INSERT INTO pool_versions (pool_id, version, site_ids, seed, contribution_rate, hit_probability)
+VALUES ('main', 1, '{1,2,3}', 100000, 0.01, 0.0001)
+ON CONFLICT (pool_id, version) DO NOTHING;
+
+-- Pool configuration each wager was played under.
+ALTER TABLE wagers
+ADD COLUMN IF NOT EXISTS pool_id TEXT,
+ADD COLUMN IF NOT EXISTS pool_version INTEGER;
@@ -0,0 +1,6 @@
+ALTER TABLE pools
+DROP CONSTRAINT IF EXISTS pools_status_check;
+
+UPDATE pools
+SET status = 'retired'
+WHERE status IN ('closing', 'closed');
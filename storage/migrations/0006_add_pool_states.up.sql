@@ -0,0 +1,7 @@
+-- Pools now close in two steps: closing (no new wagers) and then closed.
+UPDATE pools
+SET status = 'closed'
+WHERE status = 'retired';
+
+ALTER TABLE pools
+ADD CONSTRAINT pools_status_check CHECK (status IN ('active', 'paused', 'closing', 'closed'));
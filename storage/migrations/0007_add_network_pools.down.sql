@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS cycle_site_contributions;
+
+ALTER TABLE pool_versions
+DROP COLUMN IF EXISTS site_rates;
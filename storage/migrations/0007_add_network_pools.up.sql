@@ -0,0 +1,15 @@
+-- Contribution rates for individual sites of a network pool, keyed by site id.
+-- Sites without an entry contribute at the version's contribution_rate.
+ALTER TABLE pool_versions
+ADD COLUMN IF NOT EXISTS site_rates JSONB NOT NULL DEFAULT '{}';
+
+-- What each site put into a completed cycle, the basis for settling network pools.
+CREATE TABLE IF NOT EXISTS cycle_site_contributions (
+    pool_id TEXT NOT NULL,
+    cycle_id BIGINT NOT NULL,
+    site_id INTEGER NOT NULL,
+    contributions BIGINT NOT NULL,
+    wager_count BIGINT NOT NULL,
+    PRIMARY KEY (pool_id, cycle_id, site_id),
+    FOREIGN KEY (pool_id, cycle_id) REFERENCES cycles (pool_id, cycle_id)
+);
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS wagers_pool_id_created_at_idx;
+
+ALTER TABLE wagers
+DROP COLUMN IF EXISTS win_amount,
+DROP COLUMN IF EXISTS contribution;
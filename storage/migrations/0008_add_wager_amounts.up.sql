@@ -0,0 +1,6 @@
+-- Money each wager moved through its pool, the basis for inter-site settlement.
+ALTER TABLE wagers
+ADD COLUMN IF NOT EXISTS contribution BIGINT,
+ADD COLUMN IF NOT EXISTS win_amount BIGINT;
+
+CREATE INDEX IF NOT EXISTS wagers_pool_id_created_at_idx ON wagers (pool_id, created_at);
@@ -0,0 +1,11 @@
+ALTER TABLE wagers
+DROP COLUMN IF EXISTS receipt_id,
+DROP COLUMN IF EXISTS currency,
+DROP COLUMN IF EXISTS outcome,
+ALTER COLUMN created_at DROP NOT NULL,
+ALTER COLUMN created_at TYPE TIMESTAMP USING created_at AT TIME ZONE 'UTC',
+ALTER COLUMN processed_at TYPE TIMESTAMP USING processed_at AT TIME ZONE 'UTC',
+ALTER COLUMN processed_at SET DEFAULT NOW (),
+ALTER COLUMN received_at TYPE TIMESTAMP USING received_at AT TIME ZONE 'UTC',
+ALTER COLUMN received_at SET DEFAULT NOW (),
+ALTER COLUMN user_id TYPE VARCHAR(255) USING user_id::TEXT;
@@ -0,0 +1,19 @@
+-- user_id was created as text although every producer sends an integer, and the
+-- timestamps were zone-less with received_at/processed_at defaulting to the insert time.
+-- Both timestamps now come from the gateway and the engine with each wager.
+ALTER TABLE wagers
+ALTER COLUMN user_id TYPE INTEGER USING user_id::INTEGER,
+ALTER COLUMN received_at DROP DEFAULT,
+ALTER COLUMN received_at TYPE TIMESTAMPTZ USING received_at AT TIME ZONE 'UTC',
+ALTER COLUMN processed_at DROP DEFAULT,
+ALTER COLUMN processed_at TYPE TIMESTAMPTZ USING processed_at AT TIME ZONE 'UTC',
+ALTER COLUMN created_at TYPE TIMESTAMPTZ USING created_at AT TIME ZONE 'UTC',
+ALTER COLUMN created_at SET NOT NULL,
+ADD COLUMN IF NOT EXISTS outcome TEXT CHECK (outcome IN ('won', 'lost')),
+ADD COLUMN IF NOT EXISTS currency CHAR(3),
+-- Issued by storage when the wager is recorded.
+ADD COLUMN IF NOT EXISTS receipt_id TEXT;
+
+UPDATE wagers
+SET outcome = CASE WHEN win_amount > 0 THEN 'won' ELSE 'lost' END
+WHERE outcome IS NULL AND win_amount IS NOT NULL;
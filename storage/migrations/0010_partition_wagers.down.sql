@@ -0,0 +1,33 @@
+-- Archived partitions are not restored; their rows only exist in the archive files.
+CREATE TABLE wagers_unpartitioned (
+    id UUID PRIMARY KEY,
+    site_id INTEGER NOT NULL,
+    game_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    amount DECIMAL(18, 2) NOT NULL,
+    received_at TIMESTAMPTZ,
+    processed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW (),
+    pool_id TEXT,
+    pool_version INTEGER,
+    contribution BIGINT,
+    win_amount BIGINT,
+    outcome TEXT CHECK (outcome IN ('won', 'lost')),
+    currency CHAR(3),
+    receipt_id TEXT
+);
+
+INSERT INTO wagers_unpartitioned (
+    id, site_id, game_id, user_id, amount, received_at, processed_at, created_at, pool_id,
+    pool_version, contribution, win_amount, outcome, currency, receipt_id
+)
+SELECT id, site_id, game_id, user_id, amount, received_at, processed_at, created_at, pool_id,
+       pool_version, contribution, win_amount, outcome, currency, receipt_id
+FROM wagers;
+
+DROP TABLE wager_ids;
+DROP TABLE wagers;
+
+ALTER TABLE wagers_unpartitioned RENAME TO wagers;
+ALTER INDEX wagers_unpartitioned_pkey RENAME TO wagers_pkey;
+CREATE INDEX IF NOT EXISTS wagers_pool_id_created_at_idx ON wagers (pool_id, created_at);
@@ -0,0 +1,68 @@
+-- Wagers are range-partitioned by month of created_at. The storage service creates
+-- partitions ahead of time and archives old ones (see `partitions` in its configuration).
+ALTER TABLE wagers RENAME TO wagers_unpartitioned;
+ALTER INDEX wagers_pkey RENAME TO wagers_unpartitioned_pkey;
+ALTER INDEX wagers_pool_id_created_at_idx RENAME TO wagers_unpartitioned_pool_id_created_at_idx;
+
+CREATE TABLE wagers (
+    id UUID NOT NULL,
+    site_id INTEGER NOT NULL,
+    game_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    amount DECIMAL(18, 2) NOT NULL,
+    received_at TIMESTAMPTZ,
+    processed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW (),
+    pool_id TEXT,
+    pool_version INTEGER,
+    contribution BIGINT,
+    win_amount BIGINT,
+    outcome TEXT CHECK (outcome IN ('won', 'lost')),
+    currency CHAR(3),
+    receipt_id TEXT,
+    PRIMARY KEY (id, created_at)
+) PARTITION BY RANGE (created_at);
+
+CREATE INDEX wagers_pool_id_created_at_idx ON wagers (pool_id, created_at);
+CREATE INDEX wagers_site_id_user_id_created_at_idx ON wagers (site_id, user_id, created_at);
+
+-- A partitioned table can only enforce keys that include created_at, so wager ids are
+-- kept unique here, together with where to find each wager.
+CREATE TABLE wager_ids (
+    id UUID PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX wager_ids_created_at_idx ON wager_ids (created_at);
+
+-- Monthly partitions from the oldest stored wager up to two months ahead, in UTC.
+DO $$
+DECLARE
+    month TIMESTAMP := date_trunc(
+        'month',
+        COALESCE((SELECT MIN(created_at) FROM wagers_unpartitioned), NOW ()) AT TIME ZONE 'UTC'
+    );
+BEGIN
+    WHILE month <= date_trunc('month', NOW () AT TIME ZONE 'UTC') + INTERVAL '2 months' LOOP
+        EXECUTE format(
+            'CREATE TABLE %I PARTITION OF wagers FOR VALUES FROM (%L) TO (%L)',
+            'wagers_p' || to_char(month, 'YYYYMM'),
+            month AT TIME ZONE 'UTC',
+            (month + INTERVAL '1 month') AT TIME ZONE 'UTC'
+        );
+        month := month + INTERVAL '1 month';
+    END LOOP;
+END $$;
+
+INSERT INTO wagers (
+    id, site_id, game_id, user_id, amount, received_at, processed_at, created_at, pool_id,
+    pool_version, contribution, win_amount, outcome, currency, receipt_id
+)
+SELECT id, site_id, game_id, user_id, amount, received_at, processed_at, created_at, pool_id,
+       pool_version, contribution, win_amount, outcome, currency, receipt_id
+FROM wagers_unpartitioned;
+
+INSERT INTO wager_ids (id, created_at)
+SELECT id, created_at FROM wagers_unpartitioned;
+
+DROP TABLE wagers_unpartitioned;
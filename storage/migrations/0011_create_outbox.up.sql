@@ -0,0 +1,20 @@
+-- Events written in the same transaction as the rows they describe and published to
+-- RabbitMQ afterwards by the outbox relay.
+CREATE TABLE IF NOT EXISTS outbox (
+    id BIGSERIAL PRIMARY KEY,
+    event_id UUID NOT NULL UNIQUE,
+    event_type TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW (),
+    -- Hides a claimed event from other relays until its publish has had time to be confirmed
+    locked_until TIMESTAMPTZ,
+    published_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS outbox_unpublished_idx
+    ON outbox (id)
+    WHERE published_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS outbox_published_at_idx
+    ON outbox (published_at)
+    WHERE published_at IS NOT NULL;
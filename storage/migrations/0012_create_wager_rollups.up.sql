@@ -0,0 +1,22 @@
+-- Hourly wager totals for reporting, recomputed by the storage service for recent hours.
+CREATE TABLE IF NOT EXISTS wager_rollups_hourly (
+    hour TIMESTAMPTZ NOT NULL,
+    site_id INTEGER NOT NULL,
+    game_id INTEGER NOT NULL,
+    pool_id TEXT,
+    currency CHAR(3),
+    wager_count BIGINT NOT NULL,
+    turnover DECIMAL(20, 2) NOT NULL,
+    contributions BIGINT NOT NULL,
+    payouts BIGINT NOT NULL,
+    hits BIGINT NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS wager_rollups_hourly_key
+    ON wager_rollups_hourly (hour, site_id, game_id, pool_id, currency) NULLS NOT DISTINCT;
+
+-- Up to when each rollup table reflects the wagers it is built from.
+CREATE TABLE IF NOT EXISTS rollup_state (
+    name TEXT PRIMARY KEY,
+    refreshed_until TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,3 @@
+ALTER TABLE api_keys
+    ADD COLUMN IF NOT EXISTS callback_urls TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN IF NOT EXISTS callback_secret TEXT;
@@ -0,0 +1,8 @@
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM wagers_default) THEN
+        RAISE EXCEPTION 'wagers_default still holds wagers; move them to a monthly partition first';
+    END IF;
+END $$;
+
+DROP TABLE IF EXISTS wagers_default;
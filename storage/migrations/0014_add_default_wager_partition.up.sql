@@ -0,0 +1,4 @@
+-- Catches wagers outside every monthly partition, such as ones dated past the partitions
+-- created ahead, so they are stored instead of failing the insert. The storage service
+-- moves them into their monthly partition when it creates it and reports how many are left.
+CREATE TABLE IF NOT EXISTS wagers_default PARTITION OF wagers DEFAULT;
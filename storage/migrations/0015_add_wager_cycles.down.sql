@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS wagers_pool_id_cycle_id_idx;
+
+ALTER TABLE wagers DROP COLUMN IF EXISTS cycle_id;
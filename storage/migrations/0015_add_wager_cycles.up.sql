@@ -0,0 +1,5 @@
+-- Cycle of its pool each wager played in, so settlements can be built from the wagers of
+-- each completed cycle. Wagers stored before it was recorded have none.
+ALTER TABLE wagers ADD COLUMN IF NOT EXISTS cycle_id BIGINT;
+
+CREATE INDEX IF NOT EXISTS wagers_pool_id_cycle_id_idx ON wagers (pool_id, cycle_id);
@@ -0,0 +1,144 @@
+use super::CycleRepository;
+use crate::domain::models::{CycleQuery, CycleSiteContribution, JackpotCycle};
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::instrument;
+
+pub(super) const CYCLE_COLUMNS: &str = r#"
+    pool_id, cycle_id, started_at, ended_at, seed, total_contributions, wager_count,
+    winning_wager_id, winning_site_id, win_amount, trigger_value
+"#;
+
+pub struct PostgresCycleRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresCycleRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl CycleRepository for PostgresCycleRepository {
+    #[instrument(skip(self, cycle, sites), fields(pool_id = %cycle.pool_id, cycle_id = cycle.cycle_id))]
+    async fn insert_cycle(
+        &self,
+        cycle: &JackpotCycle,
+        sites: &[CycleSiteContribution],
+    ) -> anyhow::Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(&format!(
+            r#"
+            INSERT INTO cycles ({CYCLE_COLUMNS})
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (pool_id, cycle_id) DO NOTHING
+            "#
+        ))
+        .bind(&cycle.pool_id)
+        .bind(cycle.cycle_id)
+        .bind(cycle.started_at)
+        .bind(cycle.ended_at)
+        .bind(cycle.seed)
+        .bind(cycle.total_contributions)
+        .bind(cycle.wager_count)
+        .bind(cycle.winning_wager_id)
+        .bind(cycle.winning_site_id)
+        .bind(cycle.win_amount)
+        .bind(cycle.trigger_value)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        for site in sites {
+            sqlx::query(
+                r#"
+                INSERT INTO cycle_site_contributions
+                    (pool_id, cycle_id, site_id, contributions, wager_count)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+            )
+            .bind(&cycle.pool_id)
+            .bind(cycle.cycle_id)
+            .bind(site.site_id)
+            .bind(site.contributions)
+            .bind(site.wager_count)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    #[instrument(skip(self))]
+    async fn find_cycle(
+        &self,
+        pool_id: &str,
+        cycle_id: i64,
+    ) -> anyhow::Result<Option<JackpotCycle>> {
+        let cycle = sqlx::query_as::<_, JackpotCycle>(&format!(
+            "SELECT {CYCLE_COLUMNS} FROM cycles WHERE pool_id = $1 AND cycle_id = $2"
+        ))
+        .bind(pool_id)
+        .bind(cycle_id)
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(cycle)
+    }
+
+    #[instrument(skip(self))]
+    async fn list_cycles(
+        &self,
+        query: &CycleQuery,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<JackpotCycle>> {
+        let cycles = sqlx::query_as::<_, JackpotCycle>(&format!(
+            r#"
+            SELECT {CYCLE_COLUMNS}
+            FROM cycles
+            WHERE ($1::TEXT IS NULL OR pool_id = $1)
+              AND ($2::TIMESTAMPTZ IS NULL OR ended_at >= $2)
+              AND ($3::TIMESTAMPTZ IS NULL OR ended_at < $3)
+            ORDER BY ended_at DESC, pool_id, cycle_id DESC
+            LIMIT $4 OFFSET $5
+            "#
+        ))
+        .bind(&query.pool_id)
+        .bind(query.from)
+        .bind(query.to)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(cycles)
+    }
+
+    #[instrument(skip(self))]
+    async fn list_site_contributions(
+        &self,
+        pool_id: &str,
+        cycle_id: i64,
+    ) -> anyhow::Result<Vec<CycleSiteContribution>> {
+        let sites = sqlx::query_as::<_, CycleSiteContribution>(
+            r#"
+            SELECT site_id, contributions, wager_count
+            FROM cycle_site_contributions
+            WHERE pool_id = $1 AND cycle_id = $2
+            ORDER BY site_id
+            "#,
+        )
+        .bind(pool_id)
+        .bind(cycle_id)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(sites)
+    }
+}
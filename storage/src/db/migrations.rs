@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use sqlx::{
+    PgPool,
+    migrate::{Migrate, Migrator},
+};
+
+/// The migrations in `storage/migrations`, embedded at build time. They name no schema;
+/// the connection's `search_path` decides where they are applied.
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// Where one migration stands in the database.
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied: bool,
+    /// The applied migration differs from the embedded one.
+    pub checksum_mismatch: bool,
+}
+
+/// Creates `schema` if needed and applies every pending migration in it.
+pub async fn run(pool: &PgPool, schema: &str) -> anyhow::Result<()> {
+    create_schema(pool, schema).await?;
+    MIGRATOR.run(pool).await?;
+    Ok(())
+}
+
+/// Reverts applied migrations newer than `target`; with no target, only the latest one.
+/// Returns the versions that were reverted, newest first.
+pub async fn undo(pool: &PgPool, target: Option<i64>) -> anyhow::Result<Vec<i64>> {
+    let mut applied: Vec<i64> = applied_migrations(pool).await?.into_keys().collect();
+    applied.sort_unstable_by(|a, b| b.cmp(a));
+    let target = match target {
+        Some(target) => target,
+        None => applied.get(1).copied().unwrap_or(0),
+    };
+
+    applied.retain(|&version| version > target);
+    if !applied.is_empty() {
+        MIGRATOR.undo(pool, target).await?;
+    }
+    Ok(applied)
+}
+
+/// Every embedded migration with whether it has been applied, oldest first.
+pub async fn status(pool: &PgPool) -> anyhow::Result<Vec<MigrationStatus>> {
+    let applied = applied_migrations(pool).await?;
+    Ok(MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| {
+            let checksum = applied.get(&migration.version);
+            MigrationStatus {
+                version: migration.version,
+                description: migration.description.to_string(),
+                applied: checksum.is_some(),
+                checksum_mismatch: checksum
+                    .is_some_and(|checksum| *checksum != *migration.checksum),
+            }
+        })
+        .collect())
+}
+
+async fn create_schema(pool: &PgPool, schema: &str) -> anyhow::Result<()> {
+    let schema = schema.replace('"', "\"\"");
+    sqlx::query(&format!(r#"CREATE SCHEMA IF NOT EXISTS "{schema}""#))
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+async fn applied_migrations(pool: &PgPool) -> anyhow::Result<HashMap<i64, Vec<u8>>> {
+    // Nothing has been applied in a schema that has no migrations table yet.
+    let has_table: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(pool)
+        .await?;
+    if !has_table {
+        return Ok(HashMap::new());
+    }
+
+    let mut conn = pool.acquire().await?;
+    Ok(conn
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|migration| (migration.version, migration.checksum.into_owned()))
+        .collect())
+}
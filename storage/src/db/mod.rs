@@ -1,7 +1,14 @@
 use sqlx::{Pool, Postgres};
 use std::sync::Arc;
 
+pub mod cycle_repository;
+pub mod migrations;
+pub mod outbox_repository;
+pub mod partition_repository;
+pub mod report_repository;
+pub mod settlement_repository;
 pub mod wager_repository;
+pub mod webhook_repository;
 
 pub struct DatabaseClient {
     pool: Arc<Pool<Postgres>>,
@@ -24,10 +31,148 @@ impl DatabaseClient {
 }
 
 use async_trait::async_trait;
+use std::io::Write;
 
-use crate::domain::models::Wager;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::models::{
+    CycleQuery, CycleSiteContribution, CycleWagerTotals, DueDelivery, InsertOutcome, JackpotCycle,
+    OutboxEvent, ReportDimension, ReportQuery, ReportRow, SettlementQuery, Wager, WagerPartition,
+    WagerQuery, WagerRecord, WebhookDelivery, WebhookEndpoint,
+};
 
 #[async_trait]
 pub trait WagerRepository {
-    async fn insert_wagers(&self, wagers: Vec<Wager>) -> anyhow::Result<()>;
+    /// Records wagers not recorded yet in one transaction; returns what happened to each,
+    /// in order.
+    async fn insert_wagers(&self, wagers: Vec<Wager>) -> anyhow::Result<Vec<InsertOutcome>>;
+    async fn find_wager(&self, id: Uuid) -> anyhow::Result<Option<WagerRecord>>;
+    async fn list_wagers(
+        &self,
+        query: &WagerQuery,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<WagerRecord>>;
+}
+
+#[async_trait]
+pub trait CycleRepository {
+    /// Records a completed cycle and its per-site contributions; returns `false` if it was
+    /// already recorded.
+    async fn insert_cycle(
+        &self,
+        cycle: &JackpotCycle,
+        sites: &[CycleSiteContribution],
+    ) -> anyhow::Result<bool>;
+    async fn find_cycle(
+        &self,
+        pool_id: &str,
+        cycle_id: i64,
+    ) -> anyhow::Result<Option<JackpotCycle>>;
+    async fn list_cycles(
+        &self,
+        query: &CycleQuery,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<JackpotCycle>>;
+    async fn list_site_contributions(
+        &self,
+        pool_id: &str,
+        cycle_id: i64,
+    ) -> anyhow::Result<Vec<CycleSiteContribution>>;
+}
+
+#[async_trait]
+pub trait SettlementRepository {
+    /// Cycles won in the query's period with per-site totals of the wagers stored for them.
+    async fn settled_cycles(
+        &self,
+        query: &SettlementQuery,
+    ) -> anyhow::Result<Vec<CycleWagerTotals>>;
+}
+
+#[async_trait]
+pub trait ReportRepository {
+    /// Recomputes the hourly rollups from `lookback_secs` before the last refresh up to now;
+    /// returns the new refresh time, or `None` if another replica is refreshing them.
+    async fn refresh_rollups(&self, lookback_secs: u64) -> anyhow::Result<Option<DateTime<Utc>>>;
+    async fn rollups_refreshed_until(&self) -> anyhow::Result<Option<DateTime<Utc>>>;
+    /// Totals from the hourly rollups for the query's period and filters, broken down by
+    /// `dimensions` and currency.
+    async fn wager_totals(
+        &self,
+        query: &ReportQuery,
+        dimensions: &[ReportDimension],
+    ) -> anyhow::Result<Vec<ReportRow>>;
+}
+
+#[async_trait]
+pub trait PartitionRepository {
+    /// Monthly partitions of the wagers table, oldest first; the default partition is left out.
+    async fn list_wager_partitions(&self) -> anyhow::Result<Vec<WagerPartition>>;
+    /// Creates the partition `name` for `[starts_at, ends_at)`, moving the wagers of that
+    /// range out of the default partition.
+    async fn create_wager_partition(
+        &self,
+        name: &str,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+    ) -> anyhow::Result<()>;
+    /// Wagers in the default partition, which no monthly partition covers.
+    async fn count_default_partition_wagers(&self) -> anyhow::Result<i64>;
+    /// Writes the partition's rows to `writer` as CSV with a header line.
+    async fn export_partition(
+        &self,
+        name: &str,
+        writer: &mut (dyn Write + Send),
+    ) -> anyhow::Result<()>;
+    /// Detaches the partition from the wagers table and forgets its wager ids; with `drop`,
+    /// its table is dropped as well.
+    async fn detach_partition(&self, partition: &WagerPartition, drop: bool) -> anyhow::Result<()>;
+}
+
+#[async_trait]
+pub trait OutboxRepository {
+    /// Claims up to `limit` unpublished events, oldest first, hiding them from other relays
+    /// for `lease_secs`.
+    async fn claim_unpublished(
+        &self,
+        limit: i64,
+        lease_secs: i64,
+    ) -> anyhow::Result<Vec<OutboxEvent>>;
+    async fn mark_published(&self, ids: &[i64]) -> anyhow::Result<()>;
+    /// Deletes events published more than `older_than_secs` ago; returns how many.
+    async fn delete_published(&self, older_than_secs: i64) -> anyhow::Result<u64>;
+}
+
+#[async_trait]
+pub trait WebhookRepository {
+    async fn find_endpoint(&self, site_id: i32) -> anyhow::Result<Option<WebhookEndpoint>>;
+    /// Records a pending delivery; returns `false` if the event was already recorded for the site.
+    async fn create_delivery(
+        &self,
+        event_id: Uuid,
+        event_type: &str,
+        endpoint: &WebhookEndpoint,
+        payload: &serde_json::Value,
+    ) -> anyhow::Result<bool>;
+    async fn find_delivery(&self, id: Uuid) -> anyhow::Result<Option<WebhookDelivery>>;
+    /// Claims up to `limit` due deliveries, hiding them from other claimers for `lease_secs`.
+    async fn claim_due_deliveries(
+        &self,
+        limit: i64,
+        lease_secs: i64,
+    ) -> anyhow::Result<Vec<DueDelivery>>;
+    async fn mark_delivered(&self, id: Uuid, response_code: i32) -> anyhow::Result<()>;
+    /// Records a failed attempt; `next_attempt_at` of `None` marks the delivery as failed.
+    async fn mark_attempt_failed(
+        &self,
+        id: Uuid,
+        response_code: Option<i32>,
+        error: &str,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<()>;
+    /// Puts a delivery back in the queue with a fresh attempt budget.
+    async fn reset_delivery(&self, id: Uuid) -> anyhow::Result<bool>;
 }
@@ -0,0 +1,109 @@
+use super::OutboxRepository;
+use crate::domain::models::OutboxEvent;
+use async_trait::async_trait;
+use serde::Serialize;
+use sqlx::{PgConnection, PgPool, types::Json};
+use std::sync::Arc;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// Adds an event to the outbox on `conn`, so it is only published if the caller's
+/// transaction commits.
+pub async fn enqueue<T: Serialize + Sync>(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+    event_type: &str,
+    payload: &T,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO outbox (event_id, event_type, payload)
+        VALUES ($1, $2, $3)
+        "#,
+    )
+    .bind(event_id)
+    .bind(event_type)
+    .bind(Json(payload))
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub struct PostgresOutboxRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresOutboxRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OutboxRepository for PostgresOutboxRepository {
+    #[instrument(skip(self))]
+    async fn claim_unpublished(
+        &self,
+        limit: i64,
+        lease_secs: i64,
+    ) -> anyhow::Result<Vec<OutboxEvent>> {
+        let mut events = sqlx::query_as::<_, OutboxEvent>(
+            r#"
+            WITH due AS (
+                SELECT id
+                FROM outbox
+                WHERE published_at IS NULL
+                  AND (locked_until IS NULL OR locked_until <= NOW())
+                ORDER BY id
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            UPDATE outbox o
+            SET locked_until = NOW() + make_interval(secs => $2)
+            FROM due
+            WHERE o.id = due.id
+            RETURNING o.id, o.event_id, o.event_type, o.payload
+            "#,
+        )
+        .bind(limit)
+        .bind(lease_secs as f64)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        // RETURNING does not keep the order of the claim.
+        events.sort_unstable_by_key(|event| event.id);
+        Ok(events)
+    }
+
+    #[instrument(skip(self, ids), fields(event_count = ids.len()))]
+    async fn mark_published(&self, ids: &[i64]) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE outbox
+            SET published_at = NOW(), locked_until = NULL
+            WHERE id = ANY($1)
+            "#,
+        )
+        .bind(ids)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_published(&self, older_than_secs: i64) -> anyhow::Result<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM outbox
+            WHERE published_at < NOW() - make_interval(secs => $1)
+            "#,
+        )
+        .bind(older_than_secs as f64)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,185 @@
+use super::PartitionRepository;
+use crate::domain::models::WagerPartition;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use sqlx::{PgPool, Postgres, pool::PoolConnection, postgres::PgPoolCopyExt};
+use std::{io::Write, sync::Arc};
+use tracing::{info, instrument, warn};
+
+// Partition catching wagers outside every monthly one, created by the migrations.
+const DEFAULT_PARTITION: &str = "wagers_default";
+
+// Advisory lock held while a replica maintains partitions, scoped to the schema.
+const LOCK_KEY: &str = "hashtext(current_schema() || '.wager_partitions')";
+
+pub struct PostgresPartitionRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresPartitionRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    /// Takes the partition maintenance lock; `None` if another replica holds it.
+    pub async fn try_lock(&self) -> anyhow::Result<Option<PartitionLock>> {
+        let mut conn = self.pool.acquire().await?;
+        let locked: bool = sqlx::query_scalar(&format!("SELECT pg_try_advisory_lock({LOCK_KEY})"))
+            .fetch_one(&mut *conn)
+            .await?;
+        Ok(locked.then_some(PartitionLock { conn: Some(conn) }))
+    }
+}
+
+/// Holds the maintenance lock on its own connection until released.
+pub struct PartitionLock {
+    conn: Option<PoolConnection<Postgres>>,
+}
+
+impl PartitionLock {
+    pub async fn release(mut self) -> anyhow::Result<()> {
+        if let Some(mut conn) = self.conn.take() {
+            sqlx::query(&format!("SELECT pg_advisory_unlock({LOCK_KEY})"))
+                .execute(&mut *conn)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for PartitionLock {
+    fn drop(&mut self) {
+        // Closing the session is the only way to let go of the lock without a query.
+        if let Some(conn) = &mut self.conn {
+            warn!("Partition lock dropped without release; closing its connection");
+            conn.close_on_drop();
+        }
+    }
+}
+
+#[async_trait]
+impl PartitionRepository for PostgresPartitionRepository {
+    #[instrument(skip(self))]
+    async fn list_wager_partitions(&self) -> anyhow::Result<Vec<WagerPartition>> {
+        let partitions = sqlx::query_as::<_, WagerPartition>(
+            r#"
+            SELECT c.relname::TEXT AS name,
+                   substring(pg_get_expr(c.relpartbound, c.oid) FROM $$FROM \('([^']+)'\)$$)::TIMESTAMPTZ
+                       AS starts_at,
+                   substring(pg_get_expr(c.relpartbound, c.oid) FROM $$TO \('([^']+)'\)$$)::TIMESTAMPTZ
+                       AS ends_at
+            FROM pg_inherits i
+            JOIN pg_class c ON c.oid = i.inhrelid
+            WHERE i.inhparent = 'wagers'::REGCLASS
+              AND pg_get_expr(c.relpartbound, c.oid) <> 'DEFAULT'
+            ORDER BY starts_at
+            "#,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(partitions)
+    }
+
+    #[instrument(skip(self))]
+    async fn create_wager_partition(
+        &self,
+        name: &str,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        // DDL takes no bind parameters; the name and bounds are generated, never user input.
+        // A range cannot be attached while the default partition holds wagers in it, so the
+        // table is filled with them first.
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(&format!(
+            r#"CREATE TABLE "{name}" (LIKE wagers INCLUDING DEFAULTS INCLUDING CONSTRAINTS)"#
+        ))
+        .execute(&mut *tx)
+        .await?;
+        let moved = sqlx::query(&format!(
+            r#"
+            WITH moved AS (
+                DELETE FROM {DEFAULT_PARTITION}
+                WHERE created_at >= $1 AND created_at < $2
+                RETURNING *
+            )
+            INSERT INTO "{name}" SELECT * FROM moved
+            "#
+        ))
+        .bind(starts_at)
+        .bind(ends_at)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(&format!(
+            r#"
+            ALTER TABLE wagers ATTACH PARTITION "{name}"
+            FOR VALUES FROM ('{}') TO ('{}')
+            "#,
+            starts_at.to_rfc3339(),
+            ends_at.to_rfc3339()
+        ))
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        if moved.rows_affected() > 0 {
+            info!(
+                moved = moved.rows_affected(),
+                "Moved wagers from the default partition"
+            );
+        }
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn count_default_partition_wagers(&self) -> anyhow::Result<i64> {
+        let count = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {DEFAULT_PARTITION}"))
+            .fetch_one(&*self.pool)
+            .await?;
+
+        Ok(count)
+    }
+
+    #[instrument(skip(self, writer))]
+    async fn export_partition(
+        &self,
+        name: &str,
+        writer: &mut (dyn Write + Send),
+    ) -> anyhow::Result<()> {
+        let statement = format!(r#"COPY "{name}" TO STDOUT WITH (FORMAT csv, HEADER)"#);
+        let mut chunks = self.pool.copy_out_raw(&statement).await?;
+        while let Some(chunk) = chunks.try_next().await? {
+            writer.write_all(&chunk)?;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, partition), fields(partition = %partition.name))]
+    async fn detach_partition(&self, partition: &WagerPartition, drop: bool) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(&format!(
+            r#"ALTER TABLE wagers DETACH PARTITION "{}""#,
+            partition.name
+        ))
+        .execute(&mut *tx)
+        .await?;
+        if drop {
+            sqlx::query(&format!(r#"DROP TABLE "{}""#, partition.name))
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        // Outside the detach so inserts are not blocked while the ids are deleted.
+        sqlx::query("DELETE FROM wager_ids WHERE created_at >= $1 AND created_at < $2")
+            .bind(partition.starts_at)
+            .bind(partition.ends_at)
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use super::ReportRepository;
+use crate::domain::models::{ReportDimension, ReportPeriod, ReportQuery, ReportRow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::instrument;
+
+const HOURLY_ROLLUP: &str = "wager_rollups_hourly";
+
+// Transaction-level advisory lock held while a replica refreshes the rollups, scoped to the schema.
+const LOCK_KEY: &str = "hashtext(current_schema() || '.wager_rollups')";
+
+pub struct PostgresReportRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresReportRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ReportRepository for PostgresReportRepository {
+    #[instrument(skip(self))]
+    async fn refresh_rollups(&self, lookback_secs: u64) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let mut tx = self.pool.begin().await?;
+        let locked: bool =
+            sqlx::query_scalar(&format!("SELECT pg_try_advisory_xact_lock({LOCK_KEY})"))
+                .fetch_one(&mut *tx)
+                .await?;
+        if !locked {
+            return Ok(None);
+        }
+
+        // Hours from the lookback on are rebuilt from scratch; the first refresh starts at
+        // the oldest wager. Wagers only ever get added, so earlier hours stay as they are.
+        let since: DateTime<Utc> = sqlx::query_scalar(
+            r#"
+            SELECT date_trunc('hour', COALESCE(
+                (SELECT refreshed_until - make_interval(secs => $1)
+                 FROM rollup_state WHERE name = $2),
+                (SELECT MIN(created_at) FROM wagers),
+                NOW()
+            ), 'UTC')
+            "#,
+        )
+        .bind(lookback_secs as f64)
+        .bind(HOURLY_ROLLUP)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM wager_rollups_hourly WHERE hour >= $1")
+            .bind(since)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO wager_rollups_hourly (
+                hour, site_id, game_id, pool_id, currency,
+                wager_count, turnover, contributions, payouts, hits
+            )
+            SELECT date_trunc('hour', created_at, 'UTC'),
+                   site_id,
+                   game_id,
+                   pool_id,
+                   currency,
+                   COUNT(*),
+                   SUM(amount),
+                   COALESCE(SUM(contribution), 0),
+                   COALESCE(SUM(win_amount), 0),
+                   COUNT(*) FILTER (WHERE outcome = 'won')
+            FROM wagers
+            WHERE created_at >= $1
+            GROUP BY 1, 2, 3, 4, 5
+            "#,
+        )
+        .bind(since)
+        .execute(&mut *tx)
+        .await?;
+
+        let refreshed_until: DateTime<Utc> = sqlx::query_scalar(
+            r#"
+            INSERT INTO rollup_state (name, refreshed_until) VALUES ($1, NOW())
+            ON CONFLICT (name) DO UPDATE SET refreshed_until = EXCLUDED.refreshed_until
+            RETURNING refreshed_until
+            "#,
+        )
+        .bind(HOURLY_ROLLUP)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(refreshed_until))
+    }
+
+    #[instrument(skip(self))]
+    async fn rollups_refreshed_until(&self) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let refreshed_until =
+            sqlx::query_scalar("SELECT refreshed_until FROM rollup_state WHERE name = $1")
+                .bind(HOURLY_ROLLUP)
+                .fetch_optional(&*self.pool)
+                .await?;
+
+        Ok(refreshed_until)
+    }
+
+    #[instrument(skip(self))]
+    async fn wager_totals(
+        &self,
+        query: &ReportQuery,
+        dimensions: &[ReportDimension],
+    ) -> anyhow::Result<Vec<ReportRow>> {
+        let period_start = match query.period {
+            Some(ReportPeriod::Hour) => "hour",
+            Some(ReportPeriod::Day) => "date_trunc('day', hour, 'UTC')",
+            None => "NULL::TIMESTAMPTZ",
+        };
+        let column = |dimension, name, null| {
+            if dimensions.contains(&dimension) {
+                name
+            } else {
+                null
+            }
+        };
+        let site_id = column(ReportDimension::Site, "site_id", "NULL::INTEGER");
+        let game_id = column(ReportDimension::Game, "game_id", "NULL::INTEGER");
+        let pool_id = column(ReportDimension::Pool, "pool_id", "NULL::TEXT");
+
+        let rows = sqlx::query_as::<_, ReportRow>(&format!(
+            r#"
+            SELECT {period_start} AS period_start,
+                   {site_id} AS site_id,
+                   {game_id} AS game_id,
+                   {pool_id} AS pool_id,
+                   currency::TEXT AS currency,
+                   SUM(wager_count)::BIGINT AS wager_count,
+                   SUM(turnover)::FLOAT8 AS turnover,
+                   SUM(contributions)::BIGINT AS contributions,
+                   SUM(payouts)::BIGINT AS payouts,
+                   SUM(hits)::BIGINT AS hits,
+                   (SUM(contributions) - SUM(payouts))::BIGINT AS ggr
+            FROM wager_rollups_hourly
+            WHERE hour >= $1
+              AND hour < $2
+              AND ($3::INTEGER IS NULL OR site_id = $3)
+              AND ($4::INTEGER IS NULL OR game_id = $4)
+              AND ($5::TEXT IS NULL OR pool_id = $5)
+            GROUP BY 1, 2, 3, 4, 5
+            ORDER BY 1, 2, 3, 4, 5
+            "#
+        ))
+        .bind(query.from)
+        .bind(query.to)
+        .bind(query.site_id)
+        .bind(query.game_id)
+        .bind(&query.pool_id)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use super::{SettlementRepository, cycle_repository::CYCLE_COLUMNS};
+use crate::domain::models::{
+    CycleSiteContribution, CycleWagerTotals, JackpotCycle, SettlementQuery,
+};
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::instrument;
+
+pub struct PostgresSettlementRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresSettlementRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SettlementRepository for PostgresSettlementRepository {
+    #[instrument(skip(self))]
+    async fn settled_cycles(
+        &self,
+        query: &SettlementQuery,
+    ) -> anyhow::Result<Vec<CycleWagerTotals>> {
+        let cycles = sqlx::query_as::<_, JackpotCycle>(&format!(
+            r#"
+            SELECT {CYCLE_COLUMNS}
+            FROM cycles
+            WHERE ended_at >= $1
+              AND ended_at < $2
+              AND ($3::TEXT IS NULL OR pool_id = $3)
+            ORDER BY pool_id, cycle_id
+            "#
+        ))
+        .bind(query.from)
+        .bind(query.to)
+        .bind(&query.pool_id)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        // Every site of a cycle is needed to split its seed, so `site_id` is not filtered here.
+        let rows = sqlx::query_as::<_, (String, i64, i32, i64, i64, i64)>(
+            r#"
+            SELECT w.pool_id,
+                   w.cycle_id,
+                   w.site_id,
+                   COALESCE(SUM(w.contribution), 0)::BIGINT,
+                   COUNT(*),
+                   COALESCE(SUM(w.win_amount), 0)::BIGINT
+            FROM wagers w
+            JOIN cycles c ON c.pool_id = w.pool_id AND c.cycle_id = w.cycle_id
+            WHERE c.ended_at >= $1
+              AND c.ended_at < $2
+              AND ($3::TEXT IS NULL OR c.pool_id = $3)
+            GROUP BY w.pool_id, w.cycle_id, w.site_id
+            ORDER BY w.site_id
+            "#,
+        )
+        .bind(query.from)
+        .bind(query.to)
+        .bind(&query.pool_id)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        let mut totals: HashMap<(String, i64), (Vec<CycleSiteContribution>, i64)> = HashMap::new();
+        for (pool_id, cycle_id, site_id, contributions, wager_count, payouts) in rows {
+            let (sites, cycle_payouts) = totals.entry((pool_id, cycle_id)).or_default();
+            sites.push(CycleSiteContribution {
+                site_id,
+                contributions,
+                wager_count,
+            });
+            *cycle_payouts += payouts;
+        }
+
+        Ok(cycles
+            .into_iter()
+            .map(|cycle| {
+                let (sites, payouts) = totals
+                    .remove(&(cycle.pool_id.clone(), cycle.cycle_id))
+                    .unwrap_or_default();
+                CycleWagerTotals {
+                    cycle,
+                    sites,
+                    payouts,
+                }
+            })
+            .collect())
+    }
+}
@@ -1,9 +1,19 @@
-use super::WagerRepository;
-use crate::domain::models::Wager;
+use super::{WagerRepository, outbox_repository};
+use crate::domain::models::{InsertOutcome, Wager, WagerQuery, WagerRecord, WagerRecordedEvent};
+use crate::metrics::{DB_INSERT_DURATION, INSERT_BATCH_SIZE, WAGERS_STORED};
+use anyhow::Context;
 use async_trait::async_trait;
-use sqlx::PgPool;
-use std::sync::Arc;
-use tracing::{debug, info, instrument};
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
+use std::{sync::Arc, time::Instant};
+use tracing::{debug, info, instrument, warn};
+use uuid::Uuid;
+
+const WAGER_RECORD_COLUMNS: &str = r#"
+    id, site_id, game_id, user_id, amount::FLOAT8 AS amount,
+    pool_id, pool_version, cycle_id, contribution, win_amount, outcome, currency, receipt_id,
+    received_at, processed_at, created_at
+"#;
 
 pub struct PostgresWagerRepository {
     pool: Arc<PgPool>,
@@ -13,38 +23,209 @@ impl PostgresWagerRepository {
     pub fn new(pool: Arc<PgPool>) -> Self {
         Self { pool }
     }
-}
-
-#[async_trait]
-impl WagerRepository for PostgresWagerRepository {
-    #[instrument(skip(self, wagers), fields(wager_count = wagers.len()))]
-    async fn insert_wagers(&self, wagers: Vec<Wager>) -> anyhow::Result<()> {
-        info!("Starting to insert wagers");
 
+    async fn insert_in_transaction(
+        &self,
+        wagers: Vec<Wager>,
+    ) -> anyhow::Result<Vec<InsertOutcome>> {
         let mut tx = self.pool.begin().await?;
         debug!("Transaction started");
 
+        let mut outcomes = Vec::with_capacity(wagers.len());
         for wager in wagers {
+            // Wagers are partitioned by `created_at`, so their ids are kept unique here. `NOW()`
+            // is the transaction's start time, the same value the wager row defaults to.
+            let created_at: Option<DateTime<Utc>> = sqlx::query_scalar(
+                r#"
+                INSERT INTO wager_ids (id, created_at) VALUES ($1, NOW())
+                ON CONFLICT (id) DO NOTHING
+                RETURNING created_at
+                "#,
+            )
+            .bind(wager.id)
+            .fetch_optional(&mut *tx)
+            .await?;
+            let Some(created_at) = created_at else {
+                outcomes.push(compare_stored(&mut tx, &wager).await?);
+                continue;
+            };
+
+            let event = WagerRecordedEvent::new(&wager, created_at);
             sqlx::query(
                 r#"
-                INSERT INTO jackpot.wagers (
-                    id, site_id, game_id, user_id, amount
-                ) VALUES ($1, $2, $3, $4, $5)
+                INSERT INTO wagers (
+                    id, site_id, game_id, user_id, amount, pool_id, pool_version, cycle_id,
+                    contribution, win_amount, outcome, currency, receipt_id,
+                    received_at, processed_at
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
                 "#,
             )
             .bind(wager.id)
             .bind(wager.site_id)
             .bind(wager.game_id)
-            .bind(&wager.user_id)
+            .bind(wager.user_id)
             .bind(wager.amount)
+            .bind(wager.pool_id)
+            .bind(wager.pool_version)
+            .bind(wager.cycle_id)
+            .bind(wager.contribution)
+            .bind(wager.win_amount)
+            .bind(wager.outcome)
+            .bind(wager.currency)
+            .bind(wager.receipt_id)
+            .bind(wager.received_at)
+            .bind(wager.processed_at)
             .execute(&mut *tx)
             .await?;
+            outbox_repository::enqueue(
+                &mut tx,
+                event.event_id,
+                WagerRecordedEvent::EVENT_TYPE,
+                &event,
+            )
+            .await?;
+            outcomes.push(InsertOutcome::Inserted);
         }
 
         tx.commit().await?;
         debug!("Transaction committed");
+        Ok(outcomes)
+    }
+}
+
+/// Compares a wager whose id is already recorded with the stored one. The receipt is
+/// issued per delivery, so it is left out.
+async fn compare_stored(conn: &mut PgConnection, wager: &Wager) -> anyhow::Result<InsertOutcome> {
+    let (receipt_id, same): (Option<String>, bool) = sqlx::query_as(
+        r#"
+        SELECT receipt_id,
+               site_id = $2
+               AND game_id = $3
+               AND user_id = $4
+               AND amount = $5::NUMERIC(18, 2)
+               AND pool_id IS NOT DISTINCT FROM $6
+               AND pool_version IS NOT DISTINCT FROM $7
+               AND contribution IS NOT DISTINCT FROM $8
+               AND win_amount IS NOT DISTINCT FROM $9
+               AND outcome IS NOT DISTINCT FROM $10
+               AND currency IS NOT DISTINCT FROM $11
+               AND received_at IS NOT DISTINCT FROM $12
+               AND processed_at IS NOT DISTINCT FROM $13
+               AND cycle_id IS NOT DISTINCT FROM $14
+        FROM wagers
+        WHERE id = $1
+          AND created_at = (SELECT created_at FROM wager_ids WHERE id = $1)
+        "#,
+    )
+    .bind(wager.id)
+    .bind(wager.site_id)
+    .bind(wager.game_id)
+    .bind(wager.user_id)
+    .bind(wager.amount)
+    .bind(&wager.pool_id)
+    .bind(wager.pool_version)
+    .bind(wager.contribution)
+    .bind(wager.win_amount)
+    .bind(&wager.outcome)
+    .bind(&wager.currency)
+    .bind(wager.received_at)
+    .bind(wager.processed_at)
+    .bind(wager.cycle_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .with_context(|| format!("Wager {} has a recorded id but no row", wager.id))?;
+
+    Ok(if same {
+        debug!(wager_id = %wager.id, "Wager already recorded");
+        InsertOutcome::Duplicate { receipt_id }
+    } else {
+        warn!(wager_id = %wager.id, "Wager already recorded with a different payload");
+        InsertOutcome::Conflict { receipt_id }
+    })
+}
+
+#[async_trait]
+impl WagerRepository for PostgresWagerRepository {
+    #[instrument(skip(self, wagers), fields(wager_count = wagers.len()))]
+    async fn insert_wagers(&self, wagers: Vec<Wager>) -> anyhow::Result<Vec<InsertOutcome>> {
+        info!("Starting to insert wagers");
+
+        let wager_count = wagers.len();
+        let started_at = Instant::now();
+        INSERT_BATCH_SIZE.observe(wager_count as f64);
+
+        let result = self.insert_in_transaction(wagers).await;
+
+        DB_INSERT_DURATION
+            .with_label_values(&[if result.is_ok() { "ok" } else { "error" }])
+            .observe(started_at.elapsed().as_secs_f64());
+        let outcomes = match result {
+            Ok(outcomes) => outcomes,
+            Err(e) => {
+                WAGERS_STORED
+                    .with_label_values(&["error"])
+                    .inc_by(wager_count as u64);
+                return Err(e);
+            }
+        };
+        for outcome in &outcomes {
+            let label = match outcome {
+                InsertOutcome::Inserted => "ok",
+                InsertOutcome::Duplicate { .. } => "duplicate",
+                InsertOutcome::Conflict { .. } => "conflict",
+            };
+            WAGERS_STORED.with_label_values(&[label]).inc();
+        }
 
         info!("Finished inserting wagers");
-        Ok(())
+        Ok(outcomes)
+    }
+
+    #[instrument(skip(self))]
+    async fn find_wager(&self, id: Uuid) -> anyhow::Result<Option<WagerRecord>> {
+        let wager = sqlx::query_as::<_, WagerRecord>(&format!(
+            r#"
+            SELECT {WAGER_RECORD_COLUMNS}
+            FROM wagers
+            WHERE id = $1
+              AND created_at = (SELECT created_at FROM wager_ids WHERE id = $1)
+            "#
+        ))
+        .bind(id)
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(wager)
+    }
+
+    #[instrument(skip(self))]
+    async fn list_wagers(
+        &self,
+        query: &WagerQuery,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<WagerRecord>> {
+        let wagers = sqlx::query_as::<_, WagerRecord>(&format!(
+            r#"
+            SELECT {WAGER_RECORD_COLUMNS}
+            FROM wagers
+            WHERE site_id = $1
+              AND user_id = $2
+              AND ($3::TIMESTAMPTZ IS NULL OR created_at >= $3)
+              AND ($4::TIMESTAMPTZ IS NULL OR created_at < $4)
+            ORDER BY created_at DESC, id
+            LIMIT $5 OFFSET $6
+            "#
+        ))
+        .bind(query.site_id)
+        .bind(query.user_id)
+        .bind(query.from)
+        .bind(query.to)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(wagers)
     }
 }
@@ -0,0 +1,185 @@
+use super::WebhookRepository;
+use crate::domain::models::{DeliveryStatus, DueDelivery, WebhookDelivery, WebhookEndpoint};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, types::Json};
+use std::sync::Arc;
+use tracing::instrument;
+use uuid::Uuid;
+
+pub struct PostgresWebhookRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresWebhookRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for PostgresWebhookRepository {
+    #[instrument(skip(self))]
+    async fn find_endpoint(&self, site_id: i32) -> anyhow::Result<Option<WebhookEndpoint>> {
+        let endpoint = sqlx::query_as::<_, WebhookEndpoint>(
+            r#"
+            SELECT site_id, url, secret
+            FROM webhook_endpoints
+            WHERE site_id = $1 AND enabled
+            "#,
+        )
+        .bind(site_id)
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(endpoint)
+    }
+
+    #[instrument(skip(self, endpoint, payload), fields(site_id = endpoint.site_id))]
+    async fn create_delivery(
+        &self,
+        event_id: Uuid,
+        event_type: &str,
+        endpoint: &WebhookEndpoint,
+        payload: &serde_json::Value,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO webhook_deliveries (
+                id, event_id, event_type, site_id, url, payload, status, next_attempt_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, NOW())
+            ON CONFLICT (event_id, site_id) DO NOTHING
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(event_id)
+        .bind(event_type)
+        .bind(endpoint.site_id)
+        .bind(&endpoint.url)
+        .bind(Json(payload))
+        .bind(DeliveryStatus::Pending)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    #[instrument(skip(self))]
+    async fn find_delivery(&self, id: Uuid) -> anyhow::Result<Option<WebhookDelivery>> {
+        let delivery = sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            SELECT id, event_id, event_type, site_id, url, payload, status, attempts,
+                   last_response_code, last_error, next_attempt_at, created_at, delivered_at
+            FROM webhook_deliveries
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(delivery)
+    }
+
+    #[instrument(skip(self))]
+    async fn claim_due_deliveries(
+        &self,
+        limit: i64,
+        lease_secs: i64,
+    ) -> anyhow::Result<Vec<DueDelivery>> {
+        let deliveries = sqlx::query_as::<_, DueDelivery>(
+            r#"
+            WITH due AS (
+                SELECT d.id, e.secret
+                FROM webhook_deliveries d
+                JOIN webhook_endpoints e ON e.site_id = d.site_id
+                WHERE d.status = $1 AND d.next_attempt_at <= NOW() AND e.enabled
+                ORDER BY d.next_attempt_at
+                LIMIT $2
+                FOR UPDATE OF d SKIP LOCKED
+            )
+            UPDATE webhook_deliveries d
+            SET next_attempt_at = NOW() + make_interval(secs => $3)
+            FROM due
+            WHERE d.id = due.id
+            RETURNING d.id, d.event_type, d.url, due.secret, d.payload, d.attempts
+            "#,
+        )
+        .bind(DeliveryStatus::Pending)
+        .bind(limit)
+        .bind(lease_secs as f64)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(deliveries)
+    }
+
+    #[instrument(skip(self))]
+    async fn mark_delivered(&self, id: Uuid, response_code: i32) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2, attempts = attempts + 1, last_response_code = $3,
+                last_error = NULL, next_attempt_at = NULL, delivered_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(DeliveryStatus::Delivered)
+        .bind(response_code)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, error))]
+    async fn mark_attempt_failed(
+        &self,
+        id: Uuid,
+        response_code: Option<i32>,
+        error: &str,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<()> {
+        let status = if next_attempt_at.is_some() {
+            DeliveryStatus::Pending
+        } else {
+            DeliveryStatus::Failed
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2, attempts = attempts + 1, last_response_code = $3,
+                last_error = $4, next_attempt_at = $5
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(status)
+        .bind(response_code)
+        .bind(error)
+        .bind(next_attempt_at)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn reset_delivery(&self, id: Uuid) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2, attempts = 0, next_attempt_at = NOW(), delivered_at = NULL
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(DeliveryStatus::Pending)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
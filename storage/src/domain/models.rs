@@ -1,3 +1,4 @@
+use chrono::{DateTime, Timelike, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -8,6 +9,23 @@ pub struct Wager {
     pub site_id: i32,
     pub user_id: i32,
     pub game_id: i32,
+    /// Pool and configuration version the engine played the wager under.
+    pub pool_id: Option<String>,
+    pub pool_version: Option<i32>,
+    /// Cycle of the pool the wager played in.
+    pub cycle_id: Option<i64>,
+    /// What the wager added to its pool and, if it won, the jackpot paid out.
+    pub contribution: Option<i64>,
+    pub win_amount: Option<i64>,
+    /// `won` or `lost`.
+    pub outcome: Option<String>,
+    pub currency: Option<String>,
+    /// When the gateway accepted the wager and when the engine settled it.
+    pub received_at: Option<DateTime<Utc>>,
+    pub processed_at: Option<DateTime<Utc>>,
+    /// Issued by storage when the wager is recorded; never taken from the message.
+    #[serde(skip_deserializing)]
+    pub receipt_id: Option<String>,
 
     pub cheat_code: Option<String>,
 }
@@ -20,3 +38,457 @@ pub struct WagerResponse {
 
     pub receipt_id: Option<String>,
 }
+
+/// What became of a wager handed to storage.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InsertOutcome {
+    Inserted,
+    /// Already recorded with the same payload, e.g. from a redelivered message.
+    Duplicate {
+        receipt_id: Option<String>,
+    },
+    /// Already recorded with a different payload; the stored wager is kept.
+    Conflict {
+        receipt_id: Option<String>,
+    },
+}
+
+/// A stored wager as returned by the read API.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct WagerRecord {
+    pub id: Uuid,
+    pub site_id: i32,
+    pub game_id: i32,
+    pub user_id: i32,
+    pub amount: f64,
+    pub pool_id: Option<String>,
+    pub pool_version: Option<i32>,
+    pub cycle_id: Option<i64>,
+    pub contribution: Option<i64>,
+    pub win_amount: Option<i64>,
+    pub outcome: Option<String>,
+    pub currency: Option<String>,
+    pub receipt_id: Option<String>,
+    pub received_at: Option<DateTime<Utc>>,
+    pub processed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Filters for listing a user's wagers, newest first.
+#[derive(Debug, Deserialize)]
+pub struct WagerQuery {
+    pub site_id: i32,
+    pub user_id: i32,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WagerPage {
+    pub items: Vec<WagerRecord>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Published by the engine whenever a wager wins the jackpot.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct JackpotWonEvent {
+    pub event_id: Uuid,
+    pub wager_id: Uuid,
+    pub site_id: i32,
+    pub user_id: i32,
+    pub game_id: i32,
+    pub wager_amount: u64,
+    pub pool_id: String,
+    pub cycle_id: u64,
+    pub cycle_started_at: DateTime<Utc>,
+    pub seed: u64,
+    pub total_contributions: u64,
+    pub wager_count: u64,
+    pub win_amount: u64,
+    pub trigger_roll: f64,
+    pub won_at: DateTime<Utc>,
+    /// What each site put into the cycle; empty for events from older engines. Not passed
+    /// on to webhooks, which go to a single operator of a possibly shared pool.
+    #[serde(default, skip_serializing)]
+    pub site_contributions: Vec<SiteContribution>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SiteContribution {
+    pub site_id: i32,
+    pub contributions: u64,
+    pub wager_count: u64,
+}
+
+/// One site's share of a completed cycle.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct CycleSiteContribution {
+    pub site_id: i32,
+    pub contributions: i64,
+    pub wager_count: i64,
+}
+
+impl From<&SiteContribution> for CycleSiteContribution {
+    fn from(site: &SiteContribution) -> Self {
+        Self {
+            site_id: site.site_id,
+            contributions: site.contributions as i64,
+            wager_count: site.wager_count as i64,
+        }
+    }
+}
+
+/// Who owes what for a completed cycle of a (network) pool.
+///
+/// Every site is liable for what it contributed plus a share of the seed in proportion to
+/// its contributions; rounding leftovers of the seed fall to the winning site. The winning
+/// site paid the jackpot out, so its `net` is positive and the others' negative, and the
+/// `net`s sum to zero.
+#[derive(Debug, Serialize)]
+pub struct CycleSettlement {
+    pub pool_id: String,
+    pub cycle_id: i64,
+    pub winning_site_id: i32,
+    pub win_amount: i64,
+    pub seed: i64,
+    pub sites: Vec<SiteSettlement>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SiteSettlement {
+    pub site_id: i32,
+    pub contributions: i64,
+    pub wager_count: i64,
+    pub seed_share: i64,
+    /// `contributions + seed_share`: the site's part of the jackpot.
+    pub liability: i64,
+    /// The win amount for the winning site, otherwise 0.
+    pub paid_out: i64,
+    /// `paid_out - liability`; positive means the site is owed that much.
+    pub net: i64,
+}
+
+/// A completed jackpot pool cycle.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct JackpotCycle {
+    pub pool_id: String,
+    pub cycle_id: i64,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub seed: i64,
+    pub total_contributions: i64,
+    pub wager_count: i64,
+    pub winning_wager_id: Uuid,
+    pub winning_site_id: i32,
+    pub win_amount: i64,
+    pub trigger_value: Option<f64>,
+}
+
+impl From<&JackpotWonEvent> for JackpotCycle {
+    fn from(event: &JackpotWonEvent) -> Self {
+        Self {
+            pool_id: event.pool_id.clone(),
+            cycle_id: event.cycle_id as i64,
+            started_at: event.cycle_started_at,
+            ended_at: event.won_at,
+            seed: event.seed as i64,
+            total_contributions: event.total_contributions as i64,
+            wager_count: event.wager_count as i64,
+            winning_wager_id: event.wager_id,
+            winning_site_id: event.site_id,
+            win_amount: event.win_amount as i64,
+            trigger_value: Some(event.trigger_roll),
+        }
+    }
+}
+
+/// Filters for listing cycles, most recently ended first.
+#[derive(Debug, Deserialize)]
+pub struct CycleQuery {
+    pub pool_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CyclePage {
+    pub items: Vec<JackpotCycle>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// An operator's webhook endpoint, one per site.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct WebhookEndpoint {
+    pub site_id: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+/// A row of the webhook delivery log.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub site_id: i32,
+    pub url: String,
+    pub payload: sqlx::types::Json<serde_json::Value>,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub last_response_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// A pending delivery claimed for sending, with the endpoint's current secret.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DueDelivery {
+    pub id: Uuid,
+    pub event_type: String,
+    pub url: String,
+    pub secret: String,
+    pub payload: sqlx::types::Json<serde_json::Value>,
+    pub attempts: i32,
+}
+
+/// Period and filters for a settlement statement; `from` is inclusive, `to` exclusive.
+#[derive(Debug, Deserialize)]
+pub struct SettlementQuery {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub pool_id: Option<String>,
+    pub site_id: Option<i32>,
+}
+
+/// One site's settlement in one pool over the cycles won in a settlement period.
+#[derive(Debug, Serialize)]
+pub struct SettlementLine {
+    pub pool_id: String,
+    pub site_id: i32,
+    pub wager_count: i64,
+    /// What the site's wagers added to the pool.
+    pub contributions: i64,
+    /// The site's part of the seeds, split by contributions as in [`SiteSettlement`].
+    pub seed_share: i64,
+    /// Jackpots won by the site's players.
+    pub payouts: i64,
+    /// `payouts - contributions - seed_share`: positive means the pool owes the site that
+    /// much, negative that the site owes it to the pool.
+    pub net: i64,
+}
+
+/// Settlement statement for every site and pool with a cycle won in the period.
+///
+/// Each cycle is settled from its stored wagers as in [`CycleSettlement`], so the lines of a
+/// pool sum to zero. Contributions to cycles that are still open are settled in the period
+/// their cycle is won.
+#[derive(Debug, Serialize)]
+pub struct SettlementReport {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub lines: Vec<SettlementLine>,
+    /// Cycles left out because their stored wagers do not add up to the cycle the engine
+    /// recorded, e.g. while some of their wagers are still on the way to storage.
+    pub unreconciled_cycles: Vec<UnreconciledCycle>,
+}
+
+/// Per-site totals of the wagers stored for a cycle won in a settlement period.
+#[derive(Debug)]
+pub struct CycleWagerTotals {
+    pub cycle: JackpotCycle,
+    pub sites: Vec<CycleSiteContribution>,
+    /// Sum of the wagers' win amounts.
+    pub payouts: i64,
+}
+
+/// A cycle whose stored wagers disagree with the cycle the engine recorded.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct UnreconciledCycle {
+    pub pool_id: String,
+    pub cycle_id: i64,
+    pub wager_count: i64,
+    pub expected_wager_count: i64,
+    pub contributions: i64,
+    pub expected_contributions: i64,
+    pub payouts: i64,
+    pub expected_payouts: i64,
+}
+
+/// A monthly partition of the wagers table, holding `[starts_at, ends_at)`.
+#[derive(Debug, sqlx::FromRow)]
+pub struct WagerPartition {
+    pub name: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+/// Published through the outbox once a wager has been committed.
+#[derive(Debug, Serialize)]
+pub struct WagerRecordedEvent {
+    pub event_id: Uuid,
+    pub wager_id: Uuid,
+    pub site_id: i32,
+    pub user_id: i32,
+    pub game_id: i32,
+    pub amount: f64,
+    pub pool_id: Option<String>,
+    pub pool_version: Option<i32>,
+    pub contribution: Option<i64>,
+    pub win_amount: Option<i64>,
+    pub outcome: Option<String>,
+    pub currency: Option<String>,
+    pub receipt_id: Option<String>,
+    pub received_at: Option<DateTime<Utc>>,
+    pub processed_at: Option<DateTime<Utc>>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl WagerRecordedEvent {
+    pub const EVENT_TYPE: &str = "wager.recorded";
+
+    pub fn new(wager: &Wager, recorded_at: DateTime<Utc>) -> Self {
+        Self {
+            event_id: Uuid::new_v4(),
+            wager_id: wager.id,
+            site_id: wager.site_id,
+            user_id: wager.user_id,
+            game_id: wager.game_id,
+            amount: wager.amount,
+            pool_id: wager.pool_id.clone(),
+            pool_version: wager.pool_version,
+            contribution: wager.contribution,
+            win_amount: wager.win_amount,
+            outcome: wager.outcome.clone(),
+            currency: wager.currency.clone(),
+            receipt_id: wager.receipt_id.clone(),
+            received_at: wager.received_at,
+            processed_at: wager.processed_at,
+            recorded_at,
+        }
+    }
+}
+
+/// An unpublished outbox event claimed by the relay.
+#[derive(Debug, sqlx::FromRow)]
+pub struct OutboxEvent {
+    pub id: i64,
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub payload: sqlx::types::Json<serde_json::Value>,
+}
+
+/// Time buckets of a wager report, in UTC.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportPeriod {
+    Hour,
+    Day,
+}
+
+/// What the rows of a wager report are broken down by, besides currency.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportDimension {
+    Site,
+    Game,
+    Pool,
+}
+
+impl ReportDimension {
+    /// Parses a comma-separated list such as `site,pool`.
+    pub fn parse_list(list: &str) -> Result<Vec<Self>, String> {
+        let mut dimensions = Vec::new();
+        for name in list
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            let dimension = match name {
+                "site" => Self::Site,
+                "game" => Self::Game,
+                "pool" => Self::Pool,
+                other => return Err(format!("Unknown group_by `{other}`")),
+            };
+            if !dimensions.contains(&dimension) {
+                dimensions.push(dimension);
+            }
+        }
+        Ok(dimensions)
+    }
+}
+
+/// Period, breakdown and filters for a wager report. `from` is inclusive, `to` exclusive,
+/// and both fall on the hour.
+#[derive(Debug, Deserialize)]
+pub struct ReportQuery {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Totals for the whole period if not set.
+    pub period: Option<ReportPeriod>,
+    /// Comma-separated dimensions: `site`, `game` and `pool`.
+    pub group_by: Option<String>,
+    pub site_id: Option<i32>,
+    pub game_id: Option<i32>,
+    pub pool_id: Option<String>,
+}
+
+impl ReportQuery {
+    /// Checks the period and returns the dimensions to group by.
+    pub fn validate(&self) -> Result<Vec<ReportDimension>, String> {
+        if self.from >= self.to {
+            return Err("`from` must be before `to`".to_string());
+        }
+        let on_the_hour = |at: &DateTime<Utc>| at.timestamp() % 3600 == 0 && at.nanosecond() == 0;
+        if !on_the_hour(&self.from) || !on_the_hour(&self.to) {
+            return Err("`from` and `to` must be on the hour".to_string());
+        }
+        ReportDimension::parse_list(self.group_by.as_deref().unwrap_or_default())
+    }
+}
+
+/// Wager totals for one bucket of a report. Fields the report is not broken down by are null.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ReportRow {
+    pub period_start: Option<DateTime<Utc>>,
+    pub site_id: Option<i32>,
+    pub game_id: Option<i32>,
+    pub pool_id: Option<String>,
+    pub currency: Option<String>,
+    pub wager_count: i64,
+    /// Sum of the wagered amounts.
+    pub turnover: f64,
+    pub contributions: i64,
+    /// Jackpots paid out and how many wagers won them.
+    pub payouts: i64,
+    pub hits: i64,
+    /// Jackpot gross gaming revenue: `contributions - payouts`.
+    pub ggr: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WagerReport {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub period: Option<ReportPeriod>,
+    pub group_by: Vec<ReportDimension>,
+    /// Wagers recorded after this are not in the report yet.
+    pub refreshed_until: Option<DateTime<Utc>>,
+    pub rows: Vec<ReportRow>,
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use tracing::error;
+use warp::{
+    Rejection, Reply,
+    http::StatusCode,
+    reply::{json, with_status},
+};
+
+use crate::{domain::models::CycleQuery, services::cycles::CycleService};
+
+pub async fn get_cycle(
+    pool_id: String,
+    cycle_id: i64,
+    cycle_service: Arc<CycleService>,
+) -> Result<impl Reply, Rejection> {
+    match cycle_service.get_cycle(&pool_id, cycle_id).await {
+        Ok(Some(cycle)) => Ok(with_status(json(&cycle), StatusCode::OK)),
+        Ok(None) => Ok(with_status(json(&"Cycle not found"), StatusCode::NOT_FOUND)),
+        Err(e) => {
+            error!(
+                "Failed to load cycle {} of pool {}: {:?}",
+                cycle_id, pool_id, e
+            );
+            Ok(with_status(
+                json(&"Failed to load cycle"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    }
+}
+
+pub async fn get_settlement(
+    pool_id: String,
+    cycle_id: i64,
+    cycle_service: Arc<CycleService>,
+) -> Result<impl Reply, Rejection> {
+    match cycle_service.get_settlement(&pool_id, cycle_id).await {
+        Ok(Some(settlement)) => Ok(with_status(json(&settlement), StatusCode::OK)),
+        Ok(None) => Ok(with_status(
+            json(&"Settlement not found"),
+            StatusCode::NOT_FOUND,
+        )),
+        Err(e) => {
+            error!(
+                "Failed to settle cycle {} of pool {}: {:?}",
+                cycle_id, pool_id, e
+            );
+            Ok(with_status(
+                json(&"Failed to load settlement"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    }
+}
+
+pub async fn list_cycles(
+    query: CycleQuery,
+    cycle_service: Arc<CycleService>,
+) -> Result<impl Reply, Rejection> {
+    match cycle_service.list_cycles(query).await {
+        Ok(page) => Ok(with_status(json(&page), StatusCode::OK)),
+        Err(e) => {
+            error!("Failed to list cycles: {:?}", e);
+            Ok(with_status(
+                json(&"Failed to list cycles"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    }
+}
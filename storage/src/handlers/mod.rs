@@ -0,0 +1,5 @@
+pub mod cycles;
+pub mod reports;
+pub mod settlements;
+pub mod wagers;
+pub mod webhooks;
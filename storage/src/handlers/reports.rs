@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use tracing::error;
+use warp::{
+    Rejection, Reply,
+    http::StatusCode,
+    reply::{json, with_status},
+};
+
+use crate::{domain::models::ReportQuery, services::reports::ReportService};
+
+pub async fn get_wager_report(
+    query: ReportQuery,
+    report_service: Arc<ReportService>,
+) -> Result<impl Reply, Rejection> {
+    if let Err(message) = query.validate() {
+        return Ok(with_status(json(&message), StatusCode::BAD_REQUEST));
+    }
+
+    match report_service.report(query).await {
+        Ok(report) => Ok(with_status(json(&report), StatusCode::OK)),
+        Err(e) => {
+            error!("Failed to build wager report: {:?}", e);
+            Ok(with_status(
+                json(&"Failed to build wager report"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use tracing::error;
+use warp::{
+    Rejection, Reply,
+    http::StatusCode,
+    reply::{json, with_status},
+};
+
+use crate::{domain::models::SettlementQuery, services::settlement::SettlementService};
+
+pub async fn get_settlement_report(
+    query: SettlementQuery,
+    settlement_service: Arc<SettlementService>,
+) -> Result<impl Reply, Rejection> {
+    if query.from >= query.to {
+        return Ok(with_status(
+            json(&"`from` must be before `to`"),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    match settlement_service.report(query).await {
+        Ok(report) => Ok(with_status(json(&report), StatusCode::OK)),
+        Err(e) => {
+            error!("Failed to build settlement report: {:?}", e);
+            Ok(with_status(
+                json(&"Failed to build settlement report"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use tracing::error;
+use uuid::Uuid;
+use warp::{
+    Rejection, Reply,
+    http::StatusCode,
+    reply::{json, with_status},
+};
+
+use crate::{domain::models::WagerQuery, services::storage::StorageService};
+
+pub async fn get_wager(
+    id: Uuid,
+    storage_service: Arc<StorageService>,
+) -> Result<impl Reply, Rejection> {
+    match storage_service.get_wager(id).await {
+        Ok(Some(wager)) => Ok(with_status(json(&wager), StatusCode::OK)),
+        Ok(None) => Ok(with_status(json(&"Wager not found"), StatusCode::NOT_FOUND)),
+        Err(e) => {
+            error!("Failed to load wager {}: {:?}", id, e);
+            Ok(with_status(
+                json(&"Failed to load wager"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    }
+}
+
+pub async fn list_wagers(
+    query: WagerQuery,
+    storage_service: Arc<StorageService>,
+) -> Result<impl Reply, Rejection> {
+    match storage_service.list_wagers(query).await {
+        Ok(page) => Ok(with_status(json(&page), StatusCode::OK)),
+        Err(e) => {
+            error!("Failed to list wagers: {:?}", e);
+            Ok(with_status(
+                json(&"Failed to list wagers"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    }
+}
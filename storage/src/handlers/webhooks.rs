@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use tracing::error;
+use uuid::Uuid;
+use warp::{
+    Rejection, Reply,
+    http::StatusCode,
+    reply::{json, with_status},
+};
+
+use crate::services::webhook_dispatcher::WebhookDispatcher;
+
+pub async fn get_delivery(
+    id: Uuid,
+    dispatcher: Arc<WebhookDispatcher>,
+) -> Result<impl Reply, Rejection> {
+    match dispatcher.get_delivery(id).await {
+        Ok(Some(delivery)) => Ok(with_status(json(&delivery), StatusCode::OK)),
+        Ok(None) => Ok(with_status(
+            json(&"Delivery not found"),
+            StatusCode::NOT_FOUND,
+        )),
+        Err(e) => {
+            error!("Failed to load webhook delivery {}: {:?}", id, e);
+            Ok(with_status(
+                json(&"Failed to load delivery"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    }
+}
+
+pub async fn redeliver(
+    id: Uuid,
+    dispatcher: Arc<WebhookDispatcher>,
+) -> Result<impl Reply, Rejection> {
+    match dispatcher.redeliver(id).await {
+        Ok(Some(delivery)) => Ok(with_status(json(&delivery), StatusCode::ACCEPTED)),
+        Ok(None) => Ok(with_status(
+            json(&"Delivery not found"),
+            StatusCode::NOT_FOUND,
+        )),
+        Err(e) => {
+            error!("Failed to redeliver webhook {}: {:?}", id, e);
+            Ok(with_status(
+                json(&"Failed to redeliver webhook"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    }
+}
@@ -1,7 +1,9 @@
 pub mod configuration;
 pub mod db;
 pub mod domain;
+pub mod handlers;
 pub mod messaging;
+pub mod metrics;
 pub mod server;
 pub mod services;
 pub mod telemetry;
@@ -1,24 +1,139 @@
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
 use std::{
     fmt::{Debug, Display},
+    fs::File,
+    path::PathBuf,
     sync::Arc,
+    time::Duration,
 };
 use storage::{
-    configuration::get_configuration,
-    db::wager_repository::PostgresWagerRepository,
-    messaging::{connection::RabbitConnection, consumer_client::ConsumerClient},
+    configuration::{Settings, get_configuration},
+    db::{
+        cycle_repository::PostgresCycleRepository, migrations,
+        outbox_repository::PostgresOutboxRepository,
+        partition_repository::PostgresPartitionRepository,
+        report_repository::PostgresReportRepository,
+        settlement_repository::PostgresSettlementRepository,
+        wager_repository::PostgresWagerRepository, webhook_repository::PostgresWebhookRepository,
+    },
+    domain::models::SettlementQuery,
+    messaging::{
+        connection::RabbitConnection, consumer_client::ConsumerClient,
+        event_consumer::EventConsumer,
+    },
     server,
-    services::{storage::StorageService, storage_processor::TrunsatictionProcessor},
-    telemetry::{get_subscriber, init_subscriber},
+    services::{
+        cycles::CycleService,
+        outbox_relay::OutboxRelay,
+        partitions::PartitionMaintainer,
+        reports::ReportService,
+        settlement::{SettlementService, write_csv},
+        storage::StorageService,
+        storage_processor::TrunsatictionProcessor,
+        webhook_dispatcher::WebhookDispatcher,
+    },
+    telemetry::{get_subscriber, init_subscriber, shutdown_tracer_provider},
 };
-use tokio::task::JoinError;
+use tokio::{
+    signal::unix::{SignalKind, signal},
+    task::JoinError,
+};
+use tokio_util::sync::CancellationToken;
+
+/// Runs the storage service, or one of its operator commands.
+#[derive(Parser)]
+#[command(name = "storage")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Consume wagers and jackpot events and serve the HTTP API (the default).
+    Serve,
+    /// Write the inter-site settlement statement for the cycles won in a period as CSV.
+    SettlementReport {
+        /// Start of the period (inclusive), e.g. `2025-06-01T00:00:00Z`.
+        #[arg(long)]
+        from: DateTime<Utc>,
+        /// End of the period (exclusive).
+        #[arg(long)]
+        to: DateTime<Utc>,
+        #[arg(long)]
+        pool_id: Option<String>,
+        #[arg(long)]
+        site_id: Option<i32>,
+        /// File to write; defaults to stdout.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Create upcoming wager partitions and archive expired ones once, then exit.
+    MaintainPartitions,
+    /// Manage the database schema in the configured `schema_name`.
+    Migrate {
+        #[command(subcommand)]
+        command: MigrateCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum MigrateCommand {
+    /// Apply every pending migration.
+    Up,
+    /// Revert the latest applied migration, or every one newer than `--target`.
+    Down {
+        #[arg(long)]
+        target: Option<i64>,
+    },
+    /// List the migrations and whether each has been applied.
+    Status,
+}
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let subscriber = get_subscriber("storage".into(), "info".into(), std::io::stdout);
-    init_subscriber(subscriber);
-
+    let cli = Cli::parse();
     let configuration = get_configuration().expect("Failed to read configuration.");
+
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => serve(configuration).await,
+        Command::SettlementReport {
+            from,
+            to,
+            pool_id,
+            site_id,
+            output,
+        } => {
+            let query = SettlementQuery {
+                from,
+                to,
+                pool_id,
+                site_id,
+            };
+            settlement_report(configuration, query, output).await
+        }
+        Command::MaintainPartitions => maintain_partitions(configuration).await,
+        Command::Migrate { command } => migrate(configuration, command).await,
+    }
+}
+
+async fn serve(configuration: Settings) -> anyhow::Result<()> {
+    let subscriber = get_subscriber(
+        "storage".into(),
+        "info".into(),
+        std::io::stdout,
+        configuration.telemetry.otlp_endpoint.as_deref(),
+    );
+    init_subscriber(subscriber);
     let pool = sqlx::PgPool::connect(&configuration.postgres.build_url()).await?;
+    if configuration.postgres.run_migrations {
+        migrations::run(&pool, &configuration.postgres.schema_name).await?;
+        tracing::info!(
+            schema = %configuration.postgres.schema_name,
+            "Database migrations are up to date"
+        );
+    }
     let pool = Arc::new(pool);
     let wager_repository = PostgresWagerRepository::new(pool.clone());
 
@@ -27,7 +142,9 @@ async fn main() -> anyhow::Result<()> {
     // Set up RabbitMQ connection
     let storage_connection = Arc::new(RabbitConnection::new(&configuration.rabbitmq.uri).await?);
 
-    let processor = Arc::new(TrunsatictionProcessor { storage_service });
+    let processor = Arc::new(TrunsatictionProcessor {
+        storage_service: storage_service.clone(),
+    });
 
     // Set up ConsumerClient
     let consumer_client = ConsumerClient::new(
@@ -40,19 +157,115 @@ async fn main() -> anyhow::Result<()> {
     )
     .await?;
 
-    let storage_consumer = tokio::spawn(async move { consumer_client.start_consuming().await });
+    let shutdown = CancellationToken::new();
+    let drain_timeout = Duration::from_secs(configuration.application.shutdown_timeout_secs);
+    let mut storage_consumer = {
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            consumer_client
+                .start_consuming(shutdown, drain_timeout)
+                .await
+        })
+    };
+
+    // Set up webhook delivery for jackpot wins
+    let webhook_dispatcher = Arc::new(WebhookDispatcher::new(
+        PostgresWebhookRepository::new(pool.clone()),
+        configuration.webhooks.clone(),
+    )?);
+
+    let webhook_consumer = EventConsumer::new(
+        &storage_connection,
+        &configuration.rabbitmq.events_exchange,
+        "webhook_queue",
+        "webhook_consumer",
+        webhook_dispatcher.clone(),
+    )
+    .await?;
+
+    let mut webhook_consumer = {
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move { webhook_consumer.start_consuming(shutdown).await })
+    };
+    let mut webhook_delivery = {
+        let webhook_dispatcher = webhook_dispatcher.clone();
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move { webhook_dispatcher.run(shutdown).await })
+    };
+
+    // Publish events committed to the outbox, such as recorded wagers
+    let outbox_relay = OutboxRelay::new(
+        PostgresOutboxRepository::new(pool.clone()),
+        storage_connection.clone(),
+        configuration.outbox.clone(),
+    );
+    let mut outbox_relay = {
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move { outbox_relay.run(shutdown).await })
+    };
+
+    // Keep wager partitions ready ahead of time and archive expired ones
+    let partition_maintainer = PartitionMaintainer::new(
+        PostgresPartitionRepository::new(pool.clone()),
+        configuration.partitions.clone(),
+    )?;
+    let mut partition_maintenance = {
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move { partition_maintainer.run(shutdown).await })
+    };
+
+    // Keep the hourly wager rollups behind the reporting API up to date
+    let report_service = Arc::new(ReportService::new(
+        PostgresReportRepository::new(pool.clone()),
+        configuration.reports.clone(),
+    ));
+    let mut rollup_refresh = {
+        let report_service = report_service.clone();
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move { report_service.run(shutdown).await })
+    };
+
+    // Record the history of completed pool cycles
+    let cycle_service = Arc::new(CycleService::new(PostgresCycleRepository::new(
+        pool.clone(),
+    )));
+    let cycle_consumer = EventConsumer::new(
+        &storage_connection,
+        &configuration.rabbitmq.events_exchange,
+        "cycle_queue",
+        "cycle_consumer",
+        cycle_service.clone(),
+    )
+    .await?;
+    let mut cycle_consumer = {
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move { cycle_consumer.start_consuming(shutdown).await })
+    };
 
     let server_task = tokio::spawn(
         server::start_server(
             configuration.application,
             storage_connection.clone(),
             pool.clone(),
+            storage_service,
+            webhook_dispatcher,
+            cycle_service,
+            Arc::new(SettlementService::new(PostgresSettlementRepository::new(
+                pool.clone(),
+            ))),
+            report_service,
         )
         .await?,
     );
 
     tokio::select! {
-        o = storage_consumer => report_exit("Storage Consumer", o),
+        o = &mut storage_consumer => report_exit("Storage Consumer", o),
+        o = &mut webhook_consumer => report_exit("Webhook Consumer", o),
+        o = &mut webhook_delivery => report_exit("Webhook Dispatcher", o),
+        o = &mut cycle_consumer => report_exit("Cycle Consumer", o),
+        o = &mut partition_maintenance => report_exit("Partition Maintenance", o),
+        o = &mut outbox_relay => report_exit("Outbox Relay", o),
+        o = &mut rollup_refresh => report_exit("Rollup Refresh", o),
         o = server_task => {
                     match o {
                         Ok(()) => tracing::info!("Server has exited"),
@@ -63,11 +276,140 @@ async fn main() -> anyhow::Result<()> {
                         ),
                     }
                 },
+        () = shutdown_signal() => {
+            tracing::info!("Shutdown signal received, draining in-flight deliveries");
+            shutdown.cancel();
+            // Each wager is committed before its delivery is acked, so a drained consumer
+            // leaves nothing unwritten.
+            let (storage, webhooks, dispatcher, cycles, partitions, outbox, rollups) = tokio::join!(
+                storage_consumer,
+                webhook_consumer,
+                webhook_delivery,
+                cycle_consumer,
+                partition_maintenance,
+                outbox_relay,
+                rollup_refresh
+            );
+            report_exit("Storage Consumer", storage);
+            report_exit("Webhook Consumer", webhooks);
+            report_exit("Webhook Dispatcher", dispatcher);
+            report_exit("Cycle Consumer", cycles);
+            report_exit("Partition Maintenance", partitions);
+            report_exit("Outbox Relay", outbox);
+            report_exit("Rollup Refresh", rollups);
+        }
     }
 
+    storage_connection.close().await;
+    pool.close().await;
+    shutdown_tracer_provider();
     Ok(())
 }
 
+async fn settlement_report(
+    configuration: Settings,
+    query: SettlementQuery,
+    output: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    // Logs go to stderr so the CSV can be piped from stdout.
+    let subscriber = get_subscriber(
+        "storage".into(),
+        "warn".into(),
+        std::io::stderr,
+        configuration.telemetry.otlp_endpoint.as_deref(),
+    );
+    init_subscriber(subscriber);
+
+    let pool = sqlx::PgPool::connect(&configuration.postgres.build_url()).await?;
+    let settlement_service =
+        SettlementService::new(PostgresSettlementRepository::new(Arc::new(pool.clone())));
+    let report = settlement_service.report(query).await?;
+    match output {
+        Some(path) => write_csv(&report, File::create(&path)?)?,
+        None => write_csv(&report, std::io::stdout().lock())?,
+    }
+
+    pool.close().await;
+    shutdown_tracer_provider();
+    Ok(())
+}
+
+async fn maintain_partitions(configuration: Settings) -> anyhow::Result<()> {
+    let subscriber = get_subscriber(
+        "storage".into(),
+        "info".into(),
+        std::io::stderr,
+        configuration.telemetry.otlp_endpoint.as_deref(),
+    );
+    init_subscriber(subscriber);
+
+    let pool = Arc::new(sqlx::PgPool::connect(&configuration.postgres.build_url()).await?);
+    PartitionMaintainer::new(
+        PostgresPartitionRepository::new(pool.clone()),
+        configuration.partitions,
+    )?
+    .maintain(Utc::now())
+    .await?;
+
+    pool.close().await;
+    shutdown_tracer_provider();
+    Ok(())
+}
+
+async fn migrate(configuration: Settings, command: MigrateCommand) -> anyhow::Result<()> {
+    let subscriber = get_subscriber(
+        "storage".into(),
+        "warn".into(),
+        std::io::stderr,
+        configuration.telemetry.otlp_endpoint.as_deref(),
+    );
+    init_subscriber(subscriber);
+
+    let schema = &configuration.postgres.schema_name;
+    let pool = sqlx::PgPool::connect(&configuration.postgres.build_url()).await?;
+    match command {
+        MigrateCommand::Up => {
+            migrations::run(&pool, schema).await?;
+            println!("Migrations in schema {schema} are up to date");
+        }
+        MigrateCommand::Down { target } => {
+            let reverted = migrations::undo(&pool, target).await?;
+            if reverted.is_empty() {
+                println!("Nothing to revert in schema {schema}");
+            }
+            for version in reverted {
+                println!("Reverted {version} in schema {schema}");
+            }
+        }
+        MigrateCommand::Status => {
+            for migration in migrations::status(&pool).await? {
+                let state = match (migration.applied, migration.checksum_mismatch) {
+                    (true, false) => "applied",
+                    (true, true) => "applied, changed since",
+                    (false, _) => "pending",
+                };
+                println!(
+                    "{:04} {:<24} {}",
+                    migration.version, migration.description, state
+                );
+            }
+        }
+    }
+
+    pool.close().await;
+    shutdown_tracer_provider();
+    Ok(())
+}
+
+/// Resolves on SIGTERM (sent by Docker and Kubernetes) or Ctrl-C.
+async fn shutdown_signal() {
+    let mut terminate = signal(SignalKind::terminate()).expect("Failed to install SIGTERM handler");
+    tokio::select! {
+        _ = terminate.recv() => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
+}
+
 fn report_exit(task_name: &str, outcome: Result<Result<(), impl Debug + Display>, JoinError>) {
     match outcome {
         Ok(Ok(())) => {
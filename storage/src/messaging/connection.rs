@@ -27,4 +27,11 @@ impl RabbitConnection {
     pub fn is_connected(&self) -> bool {
         matches!(self.connection.status().state(), ConnectionState::Connected)
     }
+
+    /// Closes the connection and every channel opened on it.
+    pub async fn close(&self) {
+        if let Err(e) = self.connection.close(200, "shutting down").await {
+            tracing::warn!("Failed to close RabbitMQ connection: {:?}", e);
+        }
+    }
 }
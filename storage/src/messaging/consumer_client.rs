@@ -4,21 +4,30 @@ use lapin::{
     Channel, ExchangeKind,
     message::Delivery,
     options::{
-        BasicAckOptions, BasicConsumeOptions, BasicPublishOptions, ExchangeDeclareOptions,
-        QueueBindOptions, QueueDeclareOptions,
+        BasicAckOptions, BasicCancelOptions, BasicConsumeOptions, BasicPublishOptions,
+        ExchangeDeclareOptions, QueueBindOptions, QueueDeclareOptions,
     },
     types::FieldTable,
 };
-use std::sync::Arc;
-use tracing::{error, info};
+use std::{sync::Arc, time::Duration};
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+use tracing::{Instrument, error, info, info_span, warn};
 
-use super::connection::RabbitConnection;
+use super::{connection::RabbitConnection, trace_context};
 use crate::domain::models::Wager;
+use crate::metrics::QUEUE_DEPTH;
 use crate::services::storage_processor::TrunsatictionProcessor;
 
+const CONSUMER_TAG: &str = "storage_consumer";
+const QUEUE_DEPTH_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
 pub struct ConsumerClient {
     channel: Arc<Channel>,
+    // Only used to sample the queue depth: a failed passive declare closes its channel,
+    // which must not take the consumer's deliveries down with it.
+    sampler_channel: Arc<Channel>,
     queue_name: String,
+    tasks: TaskTracker,
     processor: Arc<TrunsatictionProcessor>,
 }
 
@@ -67,39 +76,92 @@ impl ConsumerClient {
             .await
             .context("Failed to bind queue")?;
 
+        let sampler_channel = Arc::new(connection.create_channel().await?);
+
         Ok(Self {
             channel,
+            sampler_channel,
             queue_name: queue_name.to_string(),
+            tasks: TaskTracker::new(),
             processor,
         })
     }
 
-    /// Starts consuming messages from the queue, processing each in a spawned task.
-    pub async fn start_consuming(&self) -> anyhow::Result<()> {
+    /// Consumes messages, processing each in a spawned task, until `shutdown` is cancelled.
+    /// The consumer is then cancelled so the broker stops delivering, and in-flight deliveries
+    /// get up to `drain_timeout` to finish and be acked before the channel is closed.
+    pub async fn start_consuming(
+        &self,
+        shutdown: CancellationToken,
+        drain_timeout: Duration,
+    ) -> anyhow::Result<()> {
         info!("Starting consumer for queue: {}", self.queue_name);
 
         let mut consumer = self
             .channel
             .basic_consume(
                 &self.queue_name,
-                "storage_consumer",
+                CONSUMER_TAG,
                 BasicConsumeOptions::default(),
                 FieldTable::default(),
             )
             .await
             .context("Failed to start consumer")?;
 
-        while let Some(delivery) = consumer.next().await {
+        self.spawn_queue_depth_sampler(shutdown.clone());
+
+        loop {
+            let delivery = tokio::select! {
+                delivery = consumer.next() => delivery,
+                () = shutdown.cancelled() => break,
+            };
+            let Some(delivery) = delivery else {
+                break;
+            };
             if let Ok(delivery) = delivery {
                 let client = self.clone();
-                tokio::spawn(async move {
-                    client.process_delivery(delivery).await;
-                });
+                let span = info_span!("consume", queue = %self.queue_name);
+                trace_context::set_parent_from_properties(&span, &delivery.properties);
+                self.tasks.spawn(
+                    async move {
+                        client.process_delivery(delivery).await;
+                    }
+                    .instrument(span),
+                );
             }
         }
+
+        self.drain(drain_timeout).await;
         Ok(())
     }
 
+    async fn drain(&self, drain_timeout: Duration) {
+        info!("Stopping consumer for queue: {}", self.queue_name);
+        if let Err(e) = self
+            .channel
+            .basic_cancel(CONSUMER_TAG, BasicCancelOptions::default())
+            .await
+        {
+            warn!("Failed to cancel consumer: {:?}", e);
+        }
+
+        self.tasks.close();
+        if tokio::time::timeout(drain_timeout, self.tasks.wait())
+            .await
+            .is_err()
+        {
+            // Unacked deliveries are requeued by the broker once the channel closes.
+            warn!(
+                in_flight = self.tasks.len(),
+                "Drain timeout elapsed, abandoning in-flight deliveries"
+            );
+        }
+
+        if let Err(e) = self.channel.close(200, "shutting down").await {
+            warn!("Failed to close channel: {:?}", e);
+        }
+    }
+
     /// Processes a single delivery: deserializes, processes, sends response, and acknowledges.
     async fn process_delivery(&self, delivery: Delivery) {
         info!("Processing delivery");
@@ -130,13 +192,15 @@ impl ConsumerClient {
                                     reply_to.as_str(),
                                     BasicPublishOptions::default(),
                                     &response_bytes,
-                                    lapin::BasicProperties::default().with_correlation_id(
-                                        delivery
-                                            .properties
-                                            .correlation_id()
-                                            .clone()
-                                            .unwrap_or_default(),
-                                    ),
+                                    lapin::BasicProperties::default()
+                                        .with_correlation_id(
+                                            delivery
+                                                .properties
+                                                .correlation_id()
+                                                .clone()
+                                                .unwrap_or_default(),
+                                        )
+                                        .with_headers(trace_context::current_headers()),
                                 )
                                 .await
                             {
@@ -161,13 +225,56 @@ impl ConsumerClient {
             }
         }
     }
+
+    /// Periodically samples the number of ready messages in the queue as a consumer lag signal,
+    /// on its own channel, until `shutdown` is cancelled.
+    fn spawn_queue_depth_sampler(&self, shutdown: CancellationToken) {
+        let channel = self.sampler_channel.clone();
+        let queue_name = self.queue_name.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(QUEUE_DEPTH_SAMPLE_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    () = shutdown.cancelled() => break,
+                }
+                match channel
+                    .queue_declare(
+                        &queue_name,
+                        QueueDeclareOptions {
+                            passive: true,
+                            ..Default::default()
+                        },
+                        FieldTable::default(),
+                    )
+                    .await
+                {
+                    Ok(queue) => QUEUE_DEPTH
+                        .with_label_values(&[queue_name.as_str()])
+                        .set(queue.message_count() as i64),
+                    Err(e) => {
+                        warn!("Failed to sample depth of queue {}: {:?}", queue_name, e);
+                        if !channel.status().connected() {
+                            warn!("Queue depth sampler channel closed, stopping sampling");
+                            return;
+                        }
+                    }
+                }
+            }
+            if let Err(e) = channel.close(200, "shutting down").await {
+                warn!("Failed to close queue depth sampler channel: {:?}", e);
+            }
+        });
+    }
 }
 
 impl Clone for ConsumerClient {
     fn clone(&self) -> Self {
         Self {
             channel: self.channel.clone(),
+            sampler_channel: self.sampler_channel.clone(),
             queue_name: self.queue_name.clone(),
+            tasks: self.tasks.clone(),
             processor: self.processor.clone(),
         }
     }
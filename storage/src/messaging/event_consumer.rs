@@ -0,0 +1,169 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use futures::StreamExt;
+use lapin::{
+    Channel, ExchangeKind,
+    message::Delivery,
+    options::{
+        BasicAckOptions, BasicCancelOptions, BasicConsumeOptions, BasicNackOptions,
+        ExchangeDeclareOptions, QueueBindOptions, QueueDeclareOptions,
+    },
+    types::FieldTable,
+};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use tracing::{Instrument, error, info, info_span, warn};
+
+use super::{connection::RabbitConnection, trace_context};
+
+/// Handles events fanned out on the jackpot events exchange.
+#[async_trait]
+pub trait EventHandler: Send + Sync + 'static {
+    type Event: DeserializeOwned + Send;
+
+    async fn handle(&self, event: Self::Event) -> anyhow::Result<()>;
+}
+
+/// Consumes a durable queue bound to the events exchange and hands each event to `H`.
+///
+/// Events are acked once handled and requeued if the handler fails, so a handler
+/// must tolerate seeing the same event twice.
+pub struct EventConsumer<H> {
+    channel: Arc<Channel>,
+    queue_name: String,
+    consumer_tag: String,
+    handler: Arc<H>,
+}
+
+impl<H: EventHandler> EventConsumer<H> {
+    pub async fn new(
+        connection: &RabbitConnection,
+        exchange_name: &str,
+        queue_name: &str,
+        consumer_tag: &str,
+        handler: Arc<H>,
+    ) -> anyhow::Result<Self> {
+        let channel = Arc::new(connection.create_channel().await?);
+
+        // Declare the exchange
+        channel
+            .exchange_declare(
+                exchange_name,
+                ExchangeKind::Fanout,
+                ExchangeDeclareOptions::default(),
+                FieldTable::default(),
+            )
+            .await
+            .context("Failed to declare exchange")?;
+
+        // Declare the queue
+        channel
+            .queue_declare(
+                queue_name,
+                QueueDeclareOptions {
+                    durable: true,
+                    ..Default::default()
+                },
+                FieldTable::default(),
+            )
+            .await
+            .context("Failed to declare queue")?;
+
+        // Bind the queue to the exchange
+        channel
+            .queue_bind(
+                queue_name,
+                exchange_name,
+                "",
+                QueueBindOptions::default(),
+                FieldTable::default(),
+            )
+            .await
+            .context("Failed to bind queue")?;
+
+        Ok(Self {
+            channel,
+            queue_name: queue_name.to_string(),
+            consumer_tag: consumer_tag.to_string(),
+            handler,
+        })
+    }
+
+    /// Consumes events, handling them one at a time in arrival order, until `shutdown` is
+    /// cancelled. The event being handled at that point is finished before the channel closes.
+    pub async fn start_consuming(&self, shutdown: CancellationToken) -> anyhow::Result<()> {
+        info!("Starting event consumer for queue: {}", self.queue_name);
+
+        let mut consumer = self
+            .channel
+            .basic_consume(
+                &self.queue_name,
+                &self.consumer_tag,
+                BasicConsumeOptions::default(),
+                FieldTable::default(),
+            )
+            .await
+            .context("Failed to start consumer")?;
+
+        loop {
+            let delivery = tokio::select! {
+                delivery = consumer.next() => delivery,
+                () = shutdown.cancelled() => break,
+            };
+            let Some(delivery) = delivery else {
+                break;
+            };
+            if let Ok(delivery) = delivery {
+                let span = info_span!("consume", queue = %self.queue_name);
+                trace_context::set_parent_from_properties(&span, &delivery.properties);
+                self.process_delivery(delivery).instrument(span).await;
+            }
+        }
+
+        info!("Stopping event consumer for queue: {}", self.queue_name);
+        if let Err(e) = self
+            .channel
+            .basic_cancel(&self.consumer_tag, BasicCancelOptions::default())
+            .await
+        {
+            warn!("Failed to cancel consumer: {:?}", e);
+        }
+        if let Err(e) = self.channel.close(200, "shutting down").await {
+            warn!("Failed to close channel: {:?}", e);
+        }
+        Ok(())
+    }
+
+    async fn process_delivery(&self, delivery: Delivery) {
+        let event: H::Event = match serde_json::from_slice(&delivery.data) {
+            Ok(event) => event,
+            Err(e) => {
+                error!("Failed to deserialize event: {:?}", e);
+                // Acknowledge the message even on failure to prevent redelivery
+                if let Err(ack_err) = delivery.ack(BasicAckOptions::default()).await {
+                    error!("Failed to acknowledge message: {:?}", ack_err);
+                }
+                return;
+            }
+        };
+
+        match self.handler.handle(event).await {
+            Ok(()) => {
+                if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
+                    error!("Failed to acknowledge message: {:?}", e);
+                }
+            }
+            Err(e) => {
+                error!("Failed to handle event: {:?}", e);
+                let options = BasicNackOptions {
+                    requeue: true,
+                    ..Default::default()
+                };
+                if let Err(nack_err) = delivery.nack(options).await {
+                    error!("Failed to reject message: {:?}", nack_err);
+                }
+            }
+        }
+    }
+}
@@ -1,2 +1,4 @@
 pub mod connection;
 pub mod consumer_client;
+pub mod event_consumer;
+pub mod trace_context;
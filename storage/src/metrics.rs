@@ -0,0 +1,77 @@
+use std::sync::LazyLock;
+
+use prometheus::{
+    Encoder, Histogram, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, TextEncoder,
+    exponential_buckets, register_histogram, register_histogram_vec, register_int_counter_vec,
+    register_int_gauge, register_int_gauge_vec,
+};
+
+pub static WAGERS_STORED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "storage_wagers_stored_total",
+        "Wagers written to Postgres, by outcome (ok, duplicate, conflict or error)",
+        &["outcome"]
+    )
+    .expect("metric can be registered")
+});
+
+pub static DB_INSERT_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "storage_db_insert_duration_seconds",
+        "Time to insert a batch of wagers, including the commit",
+        &["outcome"],
+        exponential_buckets(0.0005, 2.0, 14).expect("valid buckets")
+    )
+    .expect("metric can be registered")
+});
+
+pub static INSERT_BATCH_SIZE: LazyLock<Histogram> = LazyLock::new(|| {
+    register_histogram!(
+        "storage_insert_batch_size",
+        "Wagers per insert transaction",
+        exponential_buckets(1.0, 2.0, 11).expect("valid buckets")
+    )
+    .expect("metric can be registered")
+});
+
+pub static QUEUE_DEPTH: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "storage_queue_depth",
+        "Messages ready in a consumed queue, sampled periodically",
+        &["queue"]
+    )
+    .expect("metric can be registered")
+});
+
+pub static WEBHOOK_ATTEMPTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "storage_webhook_attempts_total",
+        "Webhook delivery attempts, by outcome (delivered, retry or failed)",
+        &["outcome"]
+    )
+    .expect("metric can be registered")
+});
+
+pub static OUTBOX_EVENTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "storage_outbox_events_total",
+        "Outbox events sent to RabbitMQ, by confirm outcome (published, returned, nacked or error)",
+        &["outcome"]
+    )
+    .expect("metric can be registered")
+});
+
+pub static DEFAULT_PARTITION_WAGERS: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!(
+        "storage_default_partition_wagers",
+        "Wagers in the default partition, outside every monthly one; checked with maintenance"
+    )
+    .expect("metric can be registered")
+});
+
+/// Renders every registered metric in the Prometheus text format.
+pub fn render() -> anyhow::Result<String> {
+    let mut buffer = Vec::new();
+    TextEncoder::new().encode(&prometheus::gather(), &mut buffer)?;
+    Ok(String::from_utf8(buffer)?)
+}
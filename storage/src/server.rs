@@ -1,17 +1,35 @@
 use crate::configuration::ApplicationSettings;
+use crate::domain::models::{CycleQuery, ReportQuery, SettlementQuery, WagerQuery};
+use crate::handlers::{cycles, reports, settlements, wagers, webhooks};
 use crate::messaging::connection::RabbitConnection;
+use crate::metrics;
+use crate::services::cycles::CycleService;
+use crate::services::reports::ReportService;
+use crate::services::settlement::SettlementService;
+use crate::services::storage::StorageService;
+use crate::services::webhook_dispatcher::WebhookDispatcher;
 use anyhow::Result;
 use sqlx::PgPool;
+use std::convert::Infallible;
 use std::future::Future;
 use std::sync::Arc;
 use tracing::{debug, info, warn};
+use uuid::Uuid;
 use warp::http::StatusCode;
 use warp::{Filter, Rejection, Reply};
 
+/// Serves the storage HTTP API. Nothing here is authenticated or scoped to a site, so the
+/// port must only be reachable on the internal network; the gateway is the public entry point.
+#[allow(clippy::too_many_arguments)]
 pub async fn start_server(
     app_config: ApplicationSettings,
     storage_connection: Arc<RabbitConnection>,
     pg_pool: Arc<PgPool>, // Add pool as a parameter
+    storage_service: Arc<StorageService>,
+    webhook_dispatcher: Arc<WebhookDispatcher>,
+    cycle_service: Arc<CycleService>,
+    settlement_service: Arc<SettlementService>,
+    report_service: Arc<ReportService>,
 ) -> Result<impl Future<Output = ()>> {
     info!("Starting server on {}:{}", app_config.host, app_config.port);
 
@@ -21,7 +39,102 @@ pub async fn start_server(
         health_check(storage_connection, pg_pool) // Pass both to health_check
     });
 
-    Ok(warp::serve(health_route).run((app_config.host, app_config.port)))
+    let wager_by_id_route = warp::path!("wagers" / Uuid)
+        .and(warp::get())
+        .and(with_storage_service(storage_service.clone()))
+        .and_then(wagers::get_wager);
+
+    let wager_list_route = warp::path!("wagers")
+        .and(warp::get())
+        .and(warp::query::<WagerQuery>())
+        .and(with_storage_service(storage_service))
+        .and_then(wagers::list_wagers);
+
+    let delivery_route = warp::path!("webhooks" / "deliveries" / Uuid)
+        .and(warp::get())
+        .and(with_webhook_dispatcher(webhook_dispatcher.clone()))
+        .and_then(webhooks::get_delivery);
+
+    let redeliver_route = warp::path!("webhooks" / "deliveries" / Uuid / "redeliver")
+        .and(warp::post())
+        .and(with_webhook_dispatcher(webhook_dispatcher))
+        .and_then(webhooks::redeliver);
+
+    let metrics_route = warp::path("metrics")
+        .and(warp::get())
+        .and_then(metrics_handler);
+
+    let cycle_route = warp::path!("cycles" / String / i64)
+        .and(warp::get())
+        .and(with_cycle_service(cycle_service.clone()))
+        .and_then(cycles::get_cycle);
+
+    let settlement_route = warp::path!("cycles" / String / i64 / "settlement")
+        .and(warp::get())
+        .and(with_cycle_service(cycle_service.clone()))
+        .and_then(cycles::get_settlement);
+
+    let cycle_list_route = warp::path!("cycles")
+        .and(warp::get())
+        .and(warp::query::<CycleQuery>())
+        .and(with_cycle_service(cycle_service))
+        .and_then(cycles::list_cycles);
+
+    let settlement_report_route = warp::path!("settlements")
+        .and(warp::get())
+        .and(warp::query::<SettlementQuery>())
+        .and(with_settlement_service(settlement_service))
+        .and_then(settlements::get_settlement_report);
+
+    let wager_report_route = warp::path!("reports" / "wagers")
+        .and(warp::get())
+        .and(warp::query::<ReportQuery>())
+        .and(with_report_service(report_service))
+        .and_then(reports::get_wager_report);
+
+    let routes = health_route
+        .or(metrics_route)
+        .or(wager_by_id_route)
+        .or(wager_list_route)
+        .or(delivery_route)
+        .or(redeliver_route)
+        .or(cycle_route)
+        .or(settlement_route)
+        .or(cycle_list_route)
+        .or(settlement_report_route)
+        .or(wager_report_route);
+
+    Ok(warp::serve(routes).run((app_config.host, app_config.port)))
+}
+
+fn with_storage_service(
+    storage_service: Arc<StorageService>,
+) -> impl Filter<Extract = (Arc<StorageService>,), Error = Infallible> + Clone {
+    warp::any().map(move || storage_service.clone())
+}
+
+fn with_cycle_service(
+    cycle_service: Arc<CycleService>,
+) -> impl Filter<Extract = (Arc<CycleService>,), Error = Infallible> + Clone {
+    warp::any().map(move || cycle_service.clone())
+}
+
+fn with_settlement_service(
+    settlement_service: Arc<SettlementService>,
+) -> impl Filter<Extract = (Arc<SettlementService>,), Error = Infallible> + Clone {
+    warp::any().map(move || settlement_service.clone())
+}
+
+fn with_report_service(
+    report_service: Arc<ReportService>,
+) -> impl Filter<Extract = (Arc<ReportService>,), Error = Infallible> + Clone {
+    warp::any().map(move || report_service.clone())
+}
+
+fn with_webhook_dispatcher(
+    webhook_dispatcher: Arc<WebhookDispatcher>,
+) -> impl Filter<Extract = (Arc<WebhookDispatcher>,), Error = Infallible> + Clone {
+    warp::any().map(move || webhook_dispatcher.clone())
 }
 
 async fn health_check(
@@ -55,3 +168,23 @@ async fn health_check(
         ))
     }
 }
+
+async fn metrics_handler() -> Result<impl Reply, Rejection> {
+    match metrics::render() {
+        Ok(body) => Ok(warp::reply::with_status(
+            warp::reply::with_header(body, "content-type", "text/plain; version=0.0.4"),
+            StatusCode::OK,
+        )),
+        Err(e) => {
+            warn!("Failed to render metrics: {:?}", e);
+            Ok(warp::reply::with_status(
+                warp::reply::with_header(
+                    "Failed to render metrics".to_string(),
+                    "content-type",
+                    "text/plain",
+                ),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use async_trait::async_trait;
+use tracing::{info, instrument};
+
+use crate::{
+    db::{CycleRepository, cycle_repository::PostgresCycleRepository},
+    domain::models::{
+        CyclePage, CycleQuery, CycleSettlement, CycleSiteContribution, JackpotCycle,
+        JackpotWonEvent, SiteSettlement,
+    },
+    messaging::event_consumer::EventHandler,
+};
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 500;
+
+/// Keeps the audit trail of completed jackpot cycles, one row per win.
+pub struct CycleService {
+    repository: PostgresCycleRepository,
+}
+
+impl CycleService {
+    pub fn new(repository: PostgresCycleRepository) -> Self {
+        Self { repository }
+    }
+
+    #[instrument(skip(self, event), fields(pool_id = %event.pool_id, cycle_id = event.cycle_id))]
+    pub async fn record_win(&self, event: &JackpotWonEvent) -> anyhow::Result<()> {
+        let sites: Vec<CycleSiteContribution> = event
+            .site_contributions
+            .iter()
+            .map(CycleSiteContribution::from)
+            .collect();
+        if self
+            .repository
+            .insert_cycle(&JackpotCycle::from(event), &sites)
+            .await?
+        {
+            info!("Recorded completed jackpot cycle");
+        }
+        Ok(())
+    }
+
+    pub async fn get_cycle(
+        &self,
+        pool_id: &str,
+        cycle_id: i64,
+    ) -> anyhow::Result<Option<JackpotCycle>> {
+        self.repository.find_cycle(pool_id, cycle_id).await
+    }
+
+    /// What each site of the cycle's pool owes or is owed; `None` if the cycle is unknown
+    /// or was recorded without per-site contributions.
+    pub async fn get_settlement(
+        &self,
+        pool_id: &str,
+        cycle_id: i64,
+    ) -> anyhow::Result<Option<CycleSettlement>> {
+        let Some(cycle) = self.repository.find_cycle(pool_id, cycle_id).await? else {
+            return Ok(None);
+        };
+        let sites = self
+            .repository
+            .list_site_contributions(pool_id, cycle_id)
+            .await?;
+        if sites.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(settle(&cycle, sites)))
+    }
+
+    pub async fn list_cycles(&self, query: CycleQuery) -> anyhow::Result<CyclePage> {
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+        let offset = query.offset.unwrap_or(0).max(0);
+
+        let items = self.repository.list_cycles(&query, limit, offset).await?;
+        Ok(CyclePage {
+            items,
+            limit,
+            offset,
+        })
+    }
+}
+
+/// Splits the seed over the sites by contributions and nets each site's liability against
+/// what it paid out.
+pub(crate) fn settle(
+    cycle: &JackpotCycle,
+    mut sites: Vec<CycleSiteContribution>,
+) -> CycleSettlement {
+    if !sites
+        .iter()
+        .any(|site| site.site_id == cycle.winning_site_id)
+    {
+        sites.push(CycleSiteContribution {
+            site_id: cycle.winning_site_id,
+            contributions: 0,
+            wager_count: 0,
+        });
+    }
+
+    let total: i64 = sites.iter().map(|site| site.contributions).sum();
+    let seed_share = |contributions: i64| {
+        if total == 0 {
+            0
+        } else {
+            (cycle.seed as i128 * contributions as i128 / total as i128) as i64
+        }
+    };
+    let seed_remainder = cycle.seed
+        - sites
+            .iter()
+            .map(|site| seed_share(site.contributions))
+            .sum::<i64>();
+
+    let sites = sites
+        .into_iter()
+        .map(|site| {
+            let won = site.site_id == cycle.winning_site_id;
+            let seed_share = seed_share(site.contributions) + if won { seed_remainder } else { 0 };
+            let liability = site.contributions + seed_share;
+            let paid_out = if won { cycle.win_amount } else { 0 };
+            SiteSettlement {
+                site_id: site.site_id,
+                contributions: site.contributions,
+                wager_count: site.wager_count,
+                seed_share,
+                liability,
+                paid_out,
+                net: paid_out - liability,
+            }
+        })
+        .collect();
+
+    CycleSettlement {
+        pool_id: cycle.pool_id.clone(),
+        cycle_id: cycle.cycle_id,
+        winning_site_id: cycle.winning_site_id,
+        win_amount: cycle.win_amount,
+        seed: cycle.seed,
+        sites,
+    }
+}
+
+#[async_trait]
+impl EventHandler for CycleService {
+    type Event = JackpotWonEvent;
+
+    async fn handle(&self, event: JackpotWonEvent) -> anyhow::Result<()> {
+        self.record_win(&event).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn cycle(seed: i64, winning_site_id: i32, sites: &[CycleSiteContribution]) -> JackpotCycle {
+        let total_contributions = sites.iter().map(|site| site.contributions).sum();
+        JackpotCycle {
+            pool_id: "main".to_string(),
+            cycle_id: 7,
+            started_at: Utc::now(),
+            ended_at: Utc::now(),
+            seed,
+            total_contributions,
+            wager_count: sites.iter().map(|site| site.wager_count).sum(),
+            winning_wager_id: Uuid::nil(),
+            winning_site_id,
+            win_amount: seed + total_contributions,
+            trigger_value: None,
+        }
+    }
+
+    fn site(site_id: i32, contributions: i64) -> CycleSiteContribution {
+        CycleSiteContribution {
+            site_id,
+            contributions,
+            wager_count: 1,
+        }
+    }
+
+    fn find(settlement: &CycleSettlement, site_id: i32) -> &SiteSettlement {
+        settlement
+            .sites
+            .iter()
+            .find(|site| site.site_id == site_id)
+            .expect("site is in the settlement")
+    }
+
+    #[test]
+    fn net_sums_to_zero() {
+        let sites = vec![site(1, 333), site(2, 667), site(3, 1_001)];
+        let cycle = cycle(10_000, 2, &sites);
+
+        let settlement = settle(&cycle, sites);
+
+        assert_eq!(settlement.sites.iter().map(|site| site.net).sum::<i64>(), 0);
+        assert_eq!(
+            settlement
+                .sites
+                .iter()
+                .map(|site| site.liability)
+                .sum::<i64>(),
+            cycle.win_amount
+        );
+        assert_eq!(find(&settlement, 2).paid_out, cycle.win_amount);
+        assert_eq!(find(&settlement, 1).paid_out, 0);
+    }
+
+    #[test]
+    fn seed_remainder_goes_to_winning_site() {
+        // 100 split 1:1:1 leaves a remainder of 1 after rounding down.
+        let sites = vec![site(1, 10), site(2, 10), site(3, 10)];
+        let cycle = cycle(100, 3, &sites);
+
+        let settlement = settle(&cycle, sites);
+
+        assert_eq!(find(&settlement, 1).seed_share, 33);
+        assert_eq!(find(&settlement, 2).seed_share, 33);
+        assert_eq!(find(&settlement, 3).seed_share, 34);
+        assert_eq!(
+            settlement
+                .sites
+                .iter()
+                .map(|site| site.seed_share)
+                .sum::<i64>(),
+            cycle.seed
+        );
+    }
+
+    #[test]
+    fn winning_site_without_contributions_is_settled() {
+        let sites = vec![site(1, 400), site(2, 600)];
+        let cycle = cycle(500, 9, &sites);
+
+        let settlement = settle(&cycle, sites);
+
+        let winner = find(&settlement, 9);
+        assert_eq!(winner.contributions, 0);
+        assert_eq!(winner.wager_count, 0);
+        assert_eq!(winner.seed_share, 0);
+        assert_eq!(winner.paid_out, cycle.win_amount);
+        assert_eq!(winner.net, cycle.win_amount);
+        assert_eq!(find(&settlement, 1).seed_share, 200);
+        assert_eq!(find(&settlement, 2).seed_share, 300);
+        assert_eq!(settlement.sites.iter().map(|site| site.net).sum::<i64>(), 0);
+    }
+}
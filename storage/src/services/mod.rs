@@ -1,2 +1,8 @@
+pub mod cycles;
+pub mod outbox_relay;
+pub mod partitions;
+pub mod reports;
+pub mod settlement;
 pub mod storage;
 pub mod storage_processor;
+pub mod webhook_dispatcher;
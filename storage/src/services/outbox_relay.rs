@@ -0,0 +1,238 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use lapin::{
+    BasicProperties, Channel, ExchangeKind,
+    options::{
+        BasicPublishOptions, ConfirmSelectOptions, ExchangeDeclareOptions, QueueBindOptions,
+        QueueDeclareOptions,
+    },
+    publisher_confirm::Confirmation,
+    types::FieldTable,
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, instrument, warn};
+
+use crate::{
+    configuration::OutboxSettings,
+    db::{OutboxRepository, outbox_repository::PostgresOutboxRepository},
+    messaging::{connection::RabbitConnection, trace_context},
+    metrics::OUTBOX_EVENTS,
+};
+
+/// How often published events past their retention are deleted.
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(600);
+const PERSISTENT: u8 = 2;
+
+/// Publishes events from the outbox table to RabbitMQ.
+///
+/// Events are marked as published only once the broker has confirmed them and routed them
+/// to at least one queue; anything else is published again after its lease runs out, so
+/// consumers see every event at least once and must tolerate duplicates, identified by the
+/// message id.
+pub struct OutboxRelay {
+    repository: PostgresOutboxRepository,
+    connection: Arc<RabbitConnection>,
+    settings: OutboxSettings,
+}
+
+impl OutboxRelay {
+    pub fn new(
+        repository: PostgresOutboxRepository,
+        connection: Arc<RabbitConnection>,
+        settings: OutboxSettings,
+    ) -> Self {
+        Self {
+            repository,
+            connection,
+            settings,
+        }
+    }
+
+    /// Publishes pending events until `shutdown` is cancelled. A batch already being
+    /// published is finished so its confirms are recorded.
+    pub async fn run(&self, shutdown: CancellationToken) -> anyhow::Result<()> {
+        info!("Starting outbox relay");
+        let poll_interval = Duration::from_secs(self.settings.poll_interval_secs);
+        let mut channel = None;
+        let mut last_cleanup = None::<Instant>;
+
+        while !shutdown.is_cancelled() {
+            let published = match self.publish_batch(&mut channel).await {
+                Ok(published) => published,
+                Err(e) => {
+                    error!("Failed to publish outbox events: {:?}", e);
+                    // Start over on a fresh channel; the batch is retried once its lease expires.
+                    // Closing the old one keeps late confirms off it and frees it on the broker.
+                    if let Some(channel) = channel.take() {
+                        close_channel(channel, "publish failed").await;
+                    }
+                    0
+                }
+            };
+
+            if last_cleanup.is_none_or(|at| at.elapsed() >= CLEANUP_INTERVAL) {
+                self.delete_expired().await;
+                last_cleanup = Some(Instant::now());
+            }
+
+            if published < self.settings.batch_size {
+                tokio::select! {
+                    _ = tokio::time::sleep(poll_interval) => {}
+                    _ = shutdown.cancelled() => {}
+                }
+            }
+        }
+
+        if let Some(channel) = channel {
+            close_channel(channel, "shutting down").await;
+        }
+        info!("Outbox relay stopped");
+        Ok(())
+    }
+
+    /// Publishes one batch of claimed events and returns how many were published.
+    #[instrument(skip_all, fields(event_count))]
+    async fn publish_batch(&self, channel: &mut Option<Channel>) -> anyhow::Result<i64> {
+        let confirm_timeout = Duration::from_secs(self.settings.confirm_timeout_secs);
+        // A claimed event is hidden from other replicas until its confirm has had time to arrive.
+        let lease_secs = self.settings.confirm_timeout_secs as i64 * 2;
+        let events = self
+            .repository
+            .claim_unpublished(self.settings.batch_size, lease_secs)
+            .await?;
+        tracing::Span::current().record("event_count", events.len());
+        if events.is_empty() {
+            return Ok(0);
+        }
+
+        if !channel
+            .as_ref()
+            .is_some_and(|channel| channel.status().connected())
+        {
+            *channel = Some(self.open_channel().await?);
+        }
+        let channel = channel.as_ref().expect("channel was just opened");
+
+        let mut confirms = Vec::with_capacity(events.len());
+        for event in &events {
+            let body = serde_json::to_vec(&event.payload)?;
+            let properties = BasicProperties::default()
+                .with_message_id(event.event_id.to_string().into())
+                .with_type(event.event_type.clone().into())
+                .with_content_type("application/json".into())
+                .with_delivery_mode(PERSISTENT)
+                .with_headers(trace_context::current_headers());
+            let confirm = channel
+                .basic_publish(
+                    &self.settings.exchange,
+                    &event.event_type,
+                    // Unroutable events come back instead of being dropped by the exchange.
+                    BasicPublishOptions {
+                        mandatory: true,
+                        ..Default::default()
+                    },
+                    &body,
+                    properties,
+                )
+                .await
+                .context("Failed to publish outbox event")?;
+            confirms.push(confirm);
+        }
+
+        let confirmations =
+            tokio::time::timeout(confirm_timeout, futures::future::join_all(confirms))
+                .await
+                .context("Timed out waiting for publisher confirms")?;
+
+        let mut published = Vec::with_capacity(events.len());
+        for (event, confirmation) in events.iter().zip(confirmations) {
+            match confirmation {
+                Ok(Confirmation::Ack(None)) => published.push(event.id),
+                Ok(Confirmation::Ack(Some(_))) => {
+                    warn!(event_id = %event.event_id, "No queue is bound for outbox event, will retry");
+                    OUTBOX_EVENTS.with_label_values(&["returned"]).inc();
+                }
+                Ok(_) => {
+                    warn!(event_id = %event.event_id, "Broker did not accept outbox event, will retry");
+                    OUTBOX_EVENTS.with_label_values(&["nacked"]).inc();
+                }
+                Err(e) => {
+                    warn!(event_id = %event.event_id, "Failed to confirm outbox event, will retry: {:?}", e);
+                    OUTBOX_EVENTS.with_label_values(&["error"]).inc();
+                }
+            }
+        }
+
+        self.repository.mark_published(&published).await?;
+        OUTBOX_EVENTS
+            .with_label_values(&["published"])
+            .inc_by(published.len() as u64);
+        Ok(published.len() as i64)
+    }
+
+    /// Opens a channel in confirm mode and declares the exchange along with a queue bound to
+    /// every routing key, so mandatory publishes always have somewhere to go.
+    async fn open_channel(&self) -> anyhow::Result<Channel> {
+        let channel = self.connection.create_channel().await?;
+        channel
+            .confirm_select(ConfirmSelectOptions::default())
+            .await
+            .context("Failed to enable publisher confirms")?;
+        channel
+            .exchange_declare(
+                &self.settings.exchange,
+                ExchangeKind::Topic,
+                ExchangeDeclareOptions {
+                    durable: true,
+                    ..Default::default()
+                },
+                FieldTable::default(),
+            )
+            .await
+            .context("Failed to declare exchange")?;
+        channel
+            .queue_declare(
+                &self.settings.queue,
+                QueueDeclareOptions {
+                    durable: true,
+                    ..Default::default()
+                },
+                FieldTable::default(),
+            )
+            .await
+            .context("Failed to declare queue")?;
+        channel
+            .queue_bind(
+                &self.settings.queue,
+                &self.settings.exchange,
+                "#",
+                QueueBindOptions::default(),
+                FieldTable::default(),
+            )
+            .await
+            .context("Failed to bind queue")?;
+        Ok(channel)
+    }
+
+    async fn delete_expired(&self) {
+        let retention_secs = (self.settings.retention_hours * 3600) as i64;
+        match self.repository.delete_published(retention_secs).await {
+            Ok(0) => {}
+            Ok(deleted) => info!(deleted, "Deleted published outbox events"),
+            Err(e) => error!("Failed to delete published outbox events: {:?}", e),
+        }
+    }
+}
+
+async fn close_channel(channel: Channel, reason: &str) {
+    if !channel.status().connected() {
+        return;
+    }
+    if let Err(e) = channel.close(200, reason).await {
+        warn!("Failed to close outbox channel: {:?}", e);
+    }
+}
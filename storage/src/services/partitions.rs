@@ -0,0 +1,166 @@
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    time::Duration,
+};
+
+use anyhow::Context;
+use chrono::{DateTime, Datelike, Months, TimeZone, Utc};
+use flate2::{Compression, write::GzEncoder};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, instrument, warn};
+
+use crate::{
+    configuration::PartitionSettings,
+    db::{PartitionRepository, partition_repository::PostgresPartitionRepository},
+    domain::models::WagerPartition,
+    metrics::DEFAULT_PARTITION_WAGERS,
+};
+
+/// Keeps monthly partitions of the wagers table ready ahead of time and archives the ones
+/// past retention to gzipped CSV files before detaching them.
+///
+/// Replicas take turns through an advisory lock, so only one maintains partitions at a time.
+pub struct PartitionMaintainer {
+    repository: PostgresPartitionRepository,
+    settings: PartitionSettings,
+}
+
+impl PartitionMaintainer {
+    /// Fails if partitions past retention would be dropped while their archives are written
+    /// somewhere that does not outlive the process, like a relative path in the container.
+    pub fn new(
+        repository: PostgresPartitionRepository,
+        settings: PartitionSettings,
+    ) -> anyhow::Result<Self> {
+        if settings.retention_months.is_some() && settings.drop_archived {
+            let directory = &settings.archive_directory;
+            anyhow::ensure!(
+                directory.is_absolute(),
+                "`partitions.archive_directory` must be absolute when `drop_archived` is set, got {}",
+                directory.display()
+            );
+            anyhow::ensure!(
+                directory.is_dir(),
+                "`partitions.archive_directory` {} must be an existing directory, such as a \
+                 mounted volume, when `drop_archived` is set",
+                directory.display()
+            );
+        }
+        Ok(Self {
+            repository,
+            settings,
+        })
+    }
+
+    pub async fn run(&self, shutdown: CancellationToken) -> anyhow::Result<()> {
+        info!("Starting wager partition maintenance");
+        let check_interval = Duration::from_secs(self.settings.check_interval_secs);
+
+        while !shutdown.is_cancelled() {
+            if let Err(e) = self.maintain(Utc::now()).await {
+                error!("Failed to maintain wager partitions: {:?}", e);
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(check_interval) => {}
+                _ = shutdown.cancelled() => {}
+            }
+        }
+
+        info!("Wager partition maintenance stopped");
+        Ok(())
+    }
+
+    /// Creates missing partitions up to `months_ahead` past the month of `now` and archives
+    /// partitions that ended more than `retention_months` before it.
+    #[instrument(skip(self))]
+    pub async fn maintain(&self, now: DateTime<Utc>) -> anyhow::Result<()> {
+        let Some(lock) = self.repository.try_lock().await? else {
+            debug!("Another replica is maintaining wager partitions");
+            return Ok(());
+        };
+        let result = self.maintain_locked(now).await;
+        lock.release().await?;
+        result
+    }
+
+    async fn maintain_locked(&self, now: DateTime<Utc>) -> anyhow::Result<()> {
+        let partitions = self.repository.list_wager_partitions().await?;
+        let current_month = month_start(now);
+
+        for ahead in 0..=self.settings.months_ahead {
+            let starts_at = current_month + Months::new(ahead);
+            let covered = partitions
+                .iter()
+                .any(|partition| partition.starts_at <= starts_at && starts_at < partition.ends_at);
+            if covered {
+                continue;
+            }
+            let name = partition_name(starts_at);
+            self.repository
+                .create_wager_partition(&name, starts_at, starts_at + Months::new(1))
+                .await?;
+            info!(partition = %name, "Created wager partition");
+        }
+
+        let stray = self.repository.count_default_partition_wagers().await?;
+        DEFAULT_PARTITION_WAGERS.set(stray);
+        if stray > 0 {
+            warn!(
+                wagers = stray,
+                "Wagers are stored in the default partition, outside every monthly one"
+            );
+        }
+
+        let Some(retention_months) = self.settings.retention_months else {
+            return Ok(());
+        };
+        let cutoff = current_month - Months::new(retention_months);
+        for partition in partitions
+            .iter()
+            .filter(|partition| partition.ends_at <= cutoff)
+        {
+            self.archive(partition).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Exports the partition, then detaches it. The file only gets its final name once it
+    /// is complete, so a crash never leaves a truncated archive looking finished.
+    #[instrument(skip(self, partition), fields(partition = %partition.name))]
+    async fn archive(&self, partition: &WagerPartition) -> anyhow::Result<()> {
+        let directory = &self.settings.archive_directory;
+        fs::create_dir_all(directory)
+            .with_context(|| format!("Failed to create {}", directory.display()))?;
+        let path = directory.join(format!("{}.csv.gz", partition.name));
+        let partial_path = directory.join(format!("{}.csv.gz.partial", partition.name));
+
+        let file = File::create(&partial_path)
+            .with_context(|| format!("Failed to create {}", partial_path.display()))?;
+        let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+        self.repository
+            .export_partition(&partition.name, &mut encoder)
+            .await?;
+        let file = encoder.finish()?.into_inner()?;
+        file.sync_all()?;
+        fs::rename(&partial_path, &path)?;
+
+        self.repository
+            .detach_partition(partition, self.settings.drop_archived)
+            .await?;
+        info!(path = %path.display(), "Archived wager partition");
+        Ok(())
+    }
+}
+
+fn month_start(at: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(at.year(), at.month(), 1, 0, 0, 0)
+        .single()
+        .expect("the first of a month at midnight UTC exists")
+}
+
+/// `wagers_pYYYYMM`, the naming used by the migration that partitioned the table.
+fn partition_name(starts_at: DateTime<Utc>) -> String {
+    format!("wagers_p{}", starts_at.format("%Y%m"))
+}
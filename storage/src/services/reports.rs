@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, instrument};
+
+use crate::{
+    configuration::ReportSettings,
+    db::{ReportRepository, report_repository::PostgresReportRepository},
+    domain::models::{ReportQuery, WagerReport},
+};
+
+/// Serves wager reports from hourly rollups and keeps the rollups up to date.
+///
+/// Replicas take turns refreshing through an advisory lock; reports say how recent their
+/// rollups are.
+pub struct ReportService {
+    repository: PostgresReportRepository,
+    settings: ReportSettings,
+}
+
+impl ReportService {
+    pub fn new(repository: PostgresReportRepository, settings: ReportSettings) -> Self {
+        Self {
+            repository,
+            settings,
+        }
+    }
+
+    /// Refreshes the rollups every `refresh_interval_secs` until `shutdown` is cancelled.
+    pub async fn run(&self, shutdown: CancellationToken) -> anyhow::Result<()> {
+        info!("Starting wager rollup refresh");
+        let refresh_interval = Duration::from_secs(self.settings.refresh_interval_secs);
+
+        while !shutdown.is_cancelled() {
+            if let Err(e) = self.refresh().await {
+                error!("Failed to refresh wager rollups: {:?}", e);
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(refresh_interval) => {}
+                _ = shutdown.cancelled() => {}
+            }
+        }
+
+        info!("Wager rollup refresh stopped");
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn refresh(&self) -> anyhow::Result<()> {
+        match self
+            .repository
+            .refresh_rollups(self.settings.lookback_secs)
+            .await?
+        {
+            Some(refreshed_until) => debug!(%refreshed_until, "Refreshed wager rollups"),
+            None => debug!("Another replica is refreshing wager rollups"),
+        }
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn report(&self, query: ReportQuery) -> anyhow::Result<WagerReport> {
+        let group_by = query.validate().map_err(anyhow::Error::msg)?;
+
+        // Read the refresh time first, so the rows are at least as recent as it says.
+        let refreshed_until = self.repository.rollups_refreshed_until().await?;
+        let rows = self.repository.wager_totals(&query, &group_by).await?;
+        Ok(WagerReport {
+            from: query.from,
+            to: query.to,
+            period: query.period,
+            group_by,
+            refreshed_until,
+            rows,
+        })
+    }
+}
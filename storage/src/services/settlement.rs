@@ -0,0 +1,217 @@
+use std::{collections::BTreeMap, io::Write};
+
+use tracing::{instrument, warn};
+
+use super::cycles::settle;
+use crate::{
+    db::{SettlementRepository, settlement_repository::PostgresSettlementRepository},
+    domain::models::{
+        CycleWagerTotals, SettlementLine, SettlementQuery, SettlementReport, UnreconciledCycle,
+    },
+};
+
+/// Builds inter-site settlement statements for pools by settling every cycle won in a period
+/// from the wagers stored for it.
+pub struct SettlementService {
+    repository: PostgresSettlementRepository,
+}
+
+impl SettlementService {
+    pub fn new(repository: PostgresSettlementRepository) -> Self {
+        Self { repository }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn report(&self, query: SettlementQuery) -> anyhow::Result<SettlementReport> {
+        anyhow::ensure!(query.from < query.to, "`from` must be before `to`");
+        let cycles = self.repository.settled_cycles(&query).await?;
+        let (lines, unreconciled_cycles) = settle_cycles(cycles, query.site_id);
+        for cycle in &unreconciled_cycles {
+            warn!(
+                pool_id = %cycle.pool_id,
+                cycle_id = cycle.cycle_id,
+                "Stored wagers do not add up to the recorded cycle, leaving it out of the settlement"
+            );
+        }
+
+        Ok(SettlementReport {
+            from: query.from,
+            to: query.to,
+            lines,
+            unreconciled_cycles,
+        })
+    }
+}
+
+/// Settles each cycle whose stored wagers reconcile with the cycle the engine recorded and
+/// sums the results per pool and site, keeping only `site_id` if given. Cycles that do not
+/// reconcile are returned instead of settled.
+fn settle_cycles(
+    cycles: Vec<CycleWagerTotals>,
+    site_id: Option<i32>,
+) -> (Vec<SettlementLine>, Vec<UnreconciledCycle>) {
+    let mut lines: BTreeMap<(String, i32), SettlementLine> = BTreeMap::new();
+    let mut unreconciled = Vec::new();
+    for CycleWagerTotals {
+        cycle,
+        sites,
+        payouts,
+    } in cycles
+    {
+        let wager_count = sites.iter().map(|site| site.wager_count).sum();
+        let contributions = sites.iter().map(|site| site.contributions).sum();
+        if wager_count != cycle.wager_count
+            || contributions != cycle.total_contributions
+            || payouts != cycle.win_amount
+        {
+            unreconciled.push(UnreconciledCycle {
+                pool_id: cycle.pool_id,
+                cycle_id: cycle.cycle_id,
+                wager_count,
+                expected_wager_count: cycle.wager_count,
+                contributions,
+                expected_contributions: cycle.total_contributions,
+                payouts,
+                expected_payouts: cycle.win_amount,
+            });
+            continue;
+        }
+
+        let settlement = settle(&cycle, sites);
+        for site in settlement.sites {
+            if site_id.is_some_and(|site_id| site_id != site.site_id) {
+                continue;
+            }
+            let line = lines
+                .entry((settlement.pool_id.clone(), site.site_id))
+                .or_insert_with(|| SettlementLine {
+                    pool_id: settlement.pool_id.clone(),
+                    site_id: site.site_id,
+                    wager_count: 0,
+                    contributions: 0,
+                    seed_share: 0,
+                    payouts: 0,
+                    net: 0,
+                });
+            line.wager_count += site.wager_count;
+            line.contributions += site.contributions;
+            line.seed_share += site.seed_share;
+            line.payouts += site.paid_out;
+            line.net += site.net;
+        }
+    }
+
+    (lines.into_values().collect(), unreconciled)
+}
+
+/// Writes the report as CSV, one row per site and pool, with the period on every row.
+pub fn write_csv(report: &SettlementReport, writer: impl Write) -> anyhow::Result<()> {
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record([
+        "period_from",
+        "period_to",
+        "pool_id",
+        "site_id",
+        "wager_count",
+        "contributions",
+        "seed_share",
+        "payouts",
+        "net",
+    ])?;
+    let from = report.from.to_rfc3339();
+    let to = report.to.to_rfc3339();
+    for line in &report.lines {
+        csv.write_record([
+            from.clone(),
+            to.clone(),
+            line.pool_id.clone(),
+            line.site_id.to_string(),
+            line.wager_count.to_string(),
+            line.contributions.to_string(),
+            line.seed_share.to_string(),
+            line.payouts.to_string(),
+            line.net.to_string(),
+        ])?;
+    }
+    csv.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::domain::models::{CycleSiteContribution, JackpotCycle};
+
+    fn totals(cycle_id: i64, sites: Vec<CycleSiteContribution>) -> CycleWagerTotals {
+        let total_contributions = sites.iter().map(|site| site.contributions).sum();
+        let win_amount = 10_000 + total_contributions;
+        CycleWagerTotals {
+            cycle: JackpotCycle {
+                pool_id: "main".to_string(),
+                cycle_id,
+                started_at: Utc::now(),
+                ended_at: Utc::now(),
+                seed: 10_000,
+                total_contributions,
+                wager_count: sites.iter().map(|site| site.wager_count).sum(),
+                winning_wager_id: Uuid::nil(),
+                winning_site_id: 1,
+                win_amount,
+                trigger_value: None,
+            },
+            sites,
+            payouts: win_amount,
+        }
+    }
+
+    fn site(site_id: i32, contributions: i64, wager_count: i64) -> CycleSiteContribution {
+        CycleSiteContribution {
+            site_id,
+            contributions,
+            wager_count,
+        }
+    }
+
+    #[test]
+    fn reconciled_cycles_net_to_zero() {
+        let cycles = vec![
+            totals(1, vec![site(1, 400, 4), site(2, 601, 6)]),
+            totals(2, vec![site(2, 250, 2), site(3, 75, 1)]),
+        ];
+
+        let (lines, unreconciled) = settle_cycles(cycles, None);
+
+        assert!(unreconciled.is_empty());
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines.iter().map(|line| line.net).sum::<i64>(), 0);
+        assert_eq!(lines.iter().map(|line| line.wager_count).sum::<i64>(), 13);
+    }
+
+    #[test]
+    fn cycle_missing_a_stored_wager_is_reported_and_left_out() {
+        let mut missing = totals(2, vec![site(2, 250, 2), site(3, 75, 1)]);
+        missing.sites[1] = site(3, 50, 0);
+        let cycles = vec![totals(1, vec![site(1, 400, 4), site(2, 601, 6)]), missing];
+
+        let (lines, unreconciled) = settle_cycles(cycles, None);
+
+        assert_eq!(
+            unreconciled,
+            vec![UnreconciledCycle {
+                pool_id: "main".to_string(),
+                cycle_id: 2,
+                wager_count: 2,
+                expected_wager_count: 3,
+                contributions: 300,
+                expected_contributions: 325,
+                payouts: 10_325,
+                expected_payouts: 10_325,
+            }]
+        );
+        assert!(lines.iter().all(|line| line.site_id != 3));
+        assert_eq!(lines.iter().map(|line| line.net).sum::<i64>(), 0);
+    }
+}
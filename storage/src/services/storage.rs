@@ -1,8 +1,12 @@
 use crate::{
     db::{WagerRepository, wager_repository::PostgresWagerRepository},
-    domain::models::Wager,
+    domain::models::{InsertOutcome, Wager, WagerPage, WagerQuery, WagerRecord},
 };
 use tracing::instrument;
+use uuid::Uuid;
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 500;
 
 pub struct StorageService {
     wager_repository: PostgresWagerRepository,
@@ -14,8 +18,32 @@ impl StorageService {
     }
 
     #[instrument(skip(self, wagers), fields(wager_count = wagers.len()))]
-    pub async fn write_transactions(&self, wagers: Vec<Wager>) -> anyhow::Result<()> {
-        self.wager_repository.insert_wagers(wagers).await?;
-        Ok(())
+    pub async fn write_transactions(
+        &self,
+        wagers: Vec<Wager>,
+    ) -> anyhow::Result<Vec<InsertOutcome>> {
+        self.wager_repository.insert_wagers(wagers).await
+    }
+
+    pub async fn get_wager(&self, id: Uuid) -> anyhow::Result<Option<WagerRecord>> {
+        self.wager_repository.find_wager(id).await
+    }
+
+    pub async fn list_wagers(&self, query: WagerQuery) -> anyhow::Result<WagerPage> {
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+        let offset = query.offset.unwrap_or(0).max(0);
+
+        let items = self
+            .wager_repository
+            .list_wagers(&query, limit, offset)
+            .await?;
+        Ok(WagerPage {
+            items,
+            limit,
+            offset,
+        })
     }
 }
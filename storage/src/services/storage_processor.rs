@@ -1,7 +1,9 @@
+use anyhow::Context;
 use std::sync::Arc;
 use tracing::instrument;
+use uuid::Uuid;
 
-use crate::domain::models::{Wager, WagerResponse};
+use crate::domain::models::{InsertOutcome, Wager, WagerResponse};
 
 use super::storage::StorageService;
 
@@ -11,20 +13,32 @@ pub struct TrunsatictionProcessor {
 
 impl TrunsatictionProcessor {
     #[instrument(name = "process_wager", skip(self, request), fields(user_id = %request.user_id, amount = request.amount))]
-    pub async fn process_wager(&self, request: Wager) -> anyhow::Result<WagerResponse> {
+    pub async fn process_wager(&self, mut request: Wager) -> anyhow::Result<WagerResponse> {
         tracing::info!("Starting wager processing, {:?}", request);
-        let mut wagers = Vec::new();
-        wagers.push(request);
+        let receipt_id = Uuid::new_v4().to_string();
+        request.receipt_id = Some(receipt_id.clone());
+        let wager_id = request.id.to_string();
+        let amount = request.amount;
 
-        self.storage_service.write_transactions(wagers).await?;
-
-        let response = WagerResponse {
-            wager_id: "aa".to_string(),
-            status: "Test".to_string(),
-            amount: 10.0, // Note: Hardcoded value from original code; consider using request.amount
-            receipt_id: Some("123".to_string()),
+        let outcome = self
+            .storage_service
+            .write_transactions(vec![request])
+            .await?
+            .pop()
+            .context("No outcome for the stored wager")?;
+        // Redelivered and conflicting wagers are answered with the receipt already issued, so
+        // the message is acked rather than failing again on every delivery.
+        let (status, receipt_id) = match outcome {
+            InsertOutcome::Inserted => ("stored", Some(receipt_id)),
+            InsertOutcome::Duplicate { receipt_id } => ("duplicate", receipt_id),
+            InsertOutcome::Conflict { receipt_id } => ("conflict", receipt_id),
         };
 
-        Ok(response)
+        Ok(WagerResponse {
+            wager_id,
+            status: status.to_string(),
+            amount,
+            receipt_id,
+        })
     }
 }
@@ -0,0 +1,494 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::StreamExt;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    configuration::WebhookSettings,
+    db::{WebhookRepository, webhook_repository::PostgresWebhookRepository},
+    domain::models::{DueDelivery, JackpotWonEvent, WebhookDelivery},
+    messaging::event_consumer::EventHandler,
+    metrics::WEBHOOK_ATTEMPTS,
+};
+
+pub const SIGNATURE_HEADER: &str = "X-Jackpot-Signature";
+pub const EVENT_TYPE_HEADER: &str = "X-Jackpot-Event";
+pub const DELIVERY_ID_HEADER: &str = "X-Jackpot-Delivery";
+
+const JACKPOT_WON: &str = "jackpot.won";
+const CLAIM_BATCH_SIZE: i64 = 100;
+const CONCURRENT_DELIVERIES: usize = 10;
+
+#[derive(Serialize)]
+struct WebhookPayload<'a, T> {
+    #[serde(rename = "type")]
+    event_type: &'a str,
+    data: &'a T,
+}
+
+/// Delivers signed jackpot win notifications to operator webhook endpoints.
+///
+/// Every delivery is written to `webhook_deliveries` first and then sent by
+/// [`WebhookDispatcher::run`], which retries failures with exponential backoff.
+pub struct WebhookDispatcher<R = PostgresWebhookRepository> {
+    repository: R,
+    http: reqwest::Client,
+    settings: WebhookSettings,
+    wake: Notify,
+}
+
+impl<R: WebhookRepository + Send + Sync> WebhookDispatcher<R> {
+    pub fn new(repository: R, settings: WebhookSettings) -> anyhow::Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_millis(settings.request_timeout_ms))
+            .build()
+            .context("Failed to build webhook HTTP client")?;
+        Ok(Self {
+            repository,
+            http,
+            settings,
+            wake: Notify::new(),
+        })
+    }
+
+    /// Records a delivery of `event` to the winning site's endpoint, if it has one.
+    #[instrument(skip(self, event), fields(event_id = %event.event_id, site_id = event.site_id))]
+    pub async fn enqueue_win(&self, event: &JackpotWonEvent) -> anyhow::Result<()> {
+        let Some(endpoint) = self.repository.find_endpoint(event.site_id).await? else {
+            debug!("No webhook endpoint configured for site");
+            return Ok(());
+        };
+
+        let payload = serde_json::to_value(WebhookPayload {
+            event_type: JACKPOT_WON,
+            data: event,
+        })?;
+        if self
+            .repository
+            .create_delivery(event.event_id, JACKPOT_WON, &endpoint, &payload)
+            .await?
+        {
+            self.wake.notify_one();
+        }
+        Ok(())
+    }
+
+    pub async fn get_delivery(&self, id: Uuid) -> anyhow::Result<Option<WebhookDelivery>> {
+        self.repository.find_delivery(id).await
+    }
+
+    /// Queues a delivery to be sent again immediately, whatever its current status.
+    #[instrument(skip(self))]
+    pub async fn redeliver(&self, id: Uuid) -> anyhow::Result<Option<WebhookDelivery>> {
+        if !self.repository.reset_delivery(id).await? {
+            return Ok(None);
+        }
+        self.wake.notify_one();
+        self.repository.find_delivery(id).await
+    }
+
+    /// Sends due deliveries until `shutdown` is cancelled. Attempts already started are
+    /// finished so their outcome is recorded; anything still leased is retried after restart.
+    pub async fn run(&self, shutdown: CancellationToken) -> anyhow::Result<()> {
+        info!("Starting webhook dispatcher");
+        let poll_interval = Duration::from_secs(self.settings.poll_interval_secs);
+        // A claimed delivery is hidden from other replicas until its request has had time to finish.
+        let lease_secs = (self.settings.request_timeout_ms / 1000 + 1) as i64 * 2;
+
+        while !shutdown.is_cancelled() {
+            let claimed = match self
+                .repository
+                .claim_due_deliveries(CLAIM_BATCH_SIZE, lease_secs)
+                .await
+            {
+                Ok(deliveries) => deliveries,
+                Err(e) => {
+                    error!("Failed to claim webhook deliveries: {:?}", e);
+                    Vec::new()
+                }
+            };
+            let claimed_count = claimed.len() as i64;
+
+            futures::stream::iter(claimed)
+                .for_each_concurrent(CONCURRENT_DELIVERIES, |delivery| self.attempt(delivery))
+                .await;
+
+            if claimed_count < CLAIM_BATCH_SIZE {
+                tokio::select! {
+                    _ = self.wake.notified() => {}
+                    _ = tokio::time::sleep(poll_interval) => {}
+                    _ = shutdown.cancelled() => {}
+                }
+            }
+        }
+
+        info!("Webhook dispatcher stopped");
+        Ok(())
+    }
+
+    #[instrument(skip(self, delivery), fields(delivery_id = %delivery.id, attempt = delivery.attempts + 1))]
+    async fn attempt(&self, delivery: DueDelivery) {
+        let body = match serde_json::to_vec(&delivery.payload) {
+            Ok(body) => body,
+            Err(e) => {
+                error!("Failed to serialize webhook payload: {:?}", e);
+                return;
+            }
+        };
+        let timestamp = Utc::now().timestamp();
+
+        let result = self
+            .http
+            .post(&delivery.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_TYPE_HEADER, &delivery.event_type)
+            .header(DELIVERY_ID_HEADER, delivery.id.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                format!(
+                    "t={},v1={}",
+                    timestamp,
+                    sign(&delivery.secret, timestamp, &body)
+                ),
+            )
+            .body(body)
+            .send()
+            .await;
+
+        let outcome = match result {
+            Ok(response) if response.status().is_success() => {
+                info!(status = response.status().as_u16(), "Webhook delivered");
+                WEBHOOK_ATTEMPTS.with_label_values(&["delivered"]).inc();
+                self.repository
+                    .mark_delivered(delivery.id, response.status().as_u16() as i32)
+                    .await
+            }
+            Ok(response) => {
+                let status = response.status();
+                self.record_failure(&delivery, Some(status.as_u16() as i32), &status.to_string())
+                    .await
+            }
+            Err(e) => self.record_failure(&delivery, None, &e.to_string()).await,
+        };
+
+        if let Err(e) = outcome {
+            error!("Failed to record webhook attempt: {:?}", e);
+        }
+    }
+
+    async fn record_failure(
+        &self,
+        delivery: &DueDelivery,
+        response_code: Option<i32>,
+        error: &str,
+    ) -> anyhow::Result<()> {
+        let attempts = delivery.attempts + 1;
+        let next_attempt_at =
+            (attempts < self.settings.max_attempts).then(|| Utc::now() + self.backoff(attempts));
+
+        match next_attempt_at {
+            Some(at) => {
+                warn!(error, next_attempt_at = %at, "Webhook delivery failed, will retry");
+                WEBHOOK_ATTEMPTS.with_label_values(&["retry"]).inc();
+            }
+            None => {
+                error!(error, "Webhook delivery failed, giving up");
+                WEBHOOK_ATTEMPTS.with_label_values(&["failed"]).inc();
+            }
+        }
+
+        self.repository
+            .mark_attempt_failed(delivery.id, response_code, error, next_attempt_at)
+            .await
+    }
+
+    /// `initial_backoff * 2^(attempts - 1)`, capped at `max_backoff`.
+    fn backoff(&self, attempts: i32) -> Duration {
+        let exponent = (attempts - 1).clamp(0, 31) as u32;
+        let secs = self
+            .settings
+            .initial_backoff_secs
+            .saturating_mul(1u64 << exponent)
+            .min(self.settings.max_backoff_secs);
+        Duration::from_secs(secs)
+    }
+}
+
+#[async_trait]
+impl<R: WebhookRepository + Send + Sync + 'static> EventHandler for WebhookDispatcher<R> {
+    type Event = JackpotWonEvent;
+
+    async fn handle(&self, event: JackpotWonEvent) -> anyhow::Result<()> {
+        self.enqueue_win(&event).await
+    }
+}
+
+/// Hex HMAC-SHA256 of `"{timestamp}.{body}"` with the endpoint's secret.
+fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{timestamp}.").as_bytes());
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use chrono::DateTime;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        time::Instant,
+    };
+
+    use super::*;
+    use crate::domain::models::WebhookEndpoint;
+
+    const SECRET: &str = "endpoint-secret";
+
+    /// One pending delivery, kept in memory in place of `webhook_deliveries`.
+    struct StubRepository {
+        delivery: Mutex<StubDelivery>,
+    }
+
+    struct StubDelivery {
+        due: DueDelivery,
+        next_attempt_at: Option<DateTime<Utc>>,
+        failures: Vec<(Option<i32>, Option<DateTime<Utc>>)>,
+        delivered: Option<i32>,
+    }
+
+    impl StubRepository {
+        /// A delivery to `url` that is due now.
+        fn due_now(url: String, payload: serde_json::Value) -> Self {
+            Self {
+                delivery: Mutex::new(StubDelivery {
+                    due: DueDelivery {
+                        id: Uuid::new_v4(),
+                        event_type: JACKPOT_WON.to_string(),
+                        url,
+                        secret: SECRET.to_string(),
+                        payload: sqlx::types::Json(payload),
+                        attempts: 0,
+                    },
+                    next_attempt_at: Some(Utc::now()),
+                    failures: Vec::new(),
+                    delivered: None,
+                }),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl WebhookRepository for StubRepository {
+        async fn find_endpoint(&self, _site_id: i32) -> anyhow::Result<Option<WebhookEndpoint>> {
+            Ok(None)
+        }
+
+        async fn create_delivery(
+            &self,
+            _event_id: Uuid,
+            _event_type: &str,
+            _endpoint: &WebhookEndpoint,
+            _payload: &serde_json::Value,
+        ) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+
+        async fn find_delivery(&self, _id: Uuid) -> anyhow::Result<Option<WebhookDelivery>> {
+            Ok(None)
+        }
+
+        async fn claim_due_deliveries(
+            &self,
+            _limit: i64,
+            _lease_secs: i64,
+        ) -> anyhow::Result<Vec<DueDelivery>> {
+            let mut delivery = self.delivery.lock().unwrap();
+            match delivery.next_attempt_at {
+                Some(at) if at <= Utc::now() => {
+                    delivery.next_attempt_at = None;
+                    Ok(vec![delivery.due.clone()])
+                }
+                _ => Ok(Vec::new()),
+            }
+        }
+
+        async fn mark_delivered(&self, _id: Uuid, response_code: i32) -> anyhow::Result<()> {
+            self.delivery.lock().unwrap().delivered = Some(response_code);
+            Ok(())
+        }
+
+        async fn mark_attempt_failed(
+            &self,
+            _id: Uuid,
+            response_code: Option<i32>,
+            _error: &str,
+            next_attempt_at: Option<DateTime<Utc>>,
+        ) -> anyhow::Result<()> {
+            let mut delivery = self.delivery.lock().unwrap();
+            delivery.due.attempts += 1;
+            delivery.next_attempt_at = next_attempt_at;
+            delivery.failures.push((response_code, next_attempt_at));
+            Ok(())
+        }
+
+        async fn reset_delivery(&self, _id: Uuid) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+    }
+
+    struct ReceivedRequest {
+        headers: String,
+        body: Vec<u8>,
+        at: Instant,
+    }
+
+    /// Serves one response per connection from `statuses`, in order, recording each request.
+    async fn stub_server(statuses: Vec<u16>) -> (String, Arc<Mutex<Vec<ReceivedRequest>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        let log = received.clone();
+        tokio::spawn(async move {
+            for status in statuses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buffer = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let (headers, body) = loop {
+                    let read = socket.read(&mut chunk).await.unwrap();
+                    buffer.extend_from_slice(&chunk[..read]);
+                    let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") else {
+                        continue;
+                    };
+                    let headers = String::from_utf8_lossy(&buffer[..end]).to_lowercase();
+                    let length: usize = headers
+                        .lines()
+                        .find_map(|line| line.strip_prefix("content-length:"))
+                        .map_or(0, |value| value.trim().parse().unwrap());
+                    if buffer.len() >= end + 4 + length {
+                        break (headers, buffer[end + 4..end + 4 + length].to_vec());
+                    }
+                };
+                log.lock().unwrap().push(ReceivedRequest {
+                    headers,
+                    body,
+                    at: Instant::now(),
+                });
+                let response = format!(
+                    "HTTP/1.1 {status} Stub\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        (url, received)
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_with_backoff_and_marks_delivery_delivered() {
+        let (url, received) = stub_server(vec![500, 200]).await;
+        let payload = serde_json::json!({ "type": JACKPOT_WON, "data": { "amount": 100 } });
+        let dispatcher = Arc::new(
+            WebhookDispatcher::new(
+                StubRepository::due_now(url, payload.clone()),
+                WebhookSettings {
+                    max_attempts: 3,
+                    initial_backoff_secs: 1,
+                    max_backoff_secs: 60,
+                    request_timeout_ms: 2_000,
+                    poll_interval_secs: 1,
+                },
+            )
+            .unwrap(),
+        );
+
+        let shutdown = CancellationToken::new();
+        let running = tokio::spawn({
+            let dispatcher = dispatcher.clone();
+            let shutdown = shutdown.clone();
+            async move { dispatcher.run(shutdown).await }
+        });
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while dispatcher
+            .repository
+            .delivery
+            .lock()
+            .unwrap()
+            .delivered
+            .is_none()
+        {
+            assert!(
+                Instant::now() < deadline,
+                "delivery was not delivered in time"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        shutdown.cancel();
+        running.await.unwrap().unwrap();
+
+        let delivery = dispatcher.repository.delivery.lock().unwrap();
+        assert_eq!(delivery.delivered, Some(200));
+        assert_eq!(delivery.failures.len(), 1);
+        let (response_code, next_attempt_at) = delivery.failures[0];
+        assert_eq!(response_code, Some(500));
+        assert!(next_attempt_at.is_some(), "a 5xx must be retried");
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert!(
+            received[1].at - received[0].at >= Duration::from_secs(1),
+            "the retry must wait for the initial backoff"
+        );
+        for request in received.iter() {
+            assert_eq!(
+                serde_json::from_slice::<serde_json::Value>(&request.body).unwrap(),
+                payload
+            );
+            let signature = request
+                .headers
+                .lines()
+                .find_map(|line| line.strip_prefix("x-jackpot-signature:"))
+                .expect("signature header is sent")
+                .trim();
+            let (timestamp, digest) = signature
+                .strip_prefix("t=")
+                .and_then(|rest| rest.split_once(",v1="))
+                .expect("signature has the t=...,v1=... format");
+            assert_eq!(
+                digest,
+                sign(SECRET, timestamp.parse().unwrap(), &request.body)
+            );
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let dispatcher = WebhookDispatcher::new(
+            StubRepository::due_now(String::new(), serde_json::Value::Null),
+            WebhookSettings {
+                max_attempts: 10,
+                initial_backoff_secs: 30,
+                max_backoff_secs: 300,
+                request_timeout_ms: 1_000,
+                poll_interval_secs: 1,
+            },
+        )
+        .unwrap();
+
+        let backoffs: Vec<u64> = (1..=6).map(|n| dispatcher.backoff(n).as_secs()).collect();
+        assert_eq!(backoffs, [30, 60, 120, 240, 300, 300]);
+    }
+}